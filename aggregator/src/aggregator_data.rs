@@ -6,18 +6,28 @@ pub use crate::aggregator_interface::Submission;
 pub const MAX_SUBMISSIONS: usize = 10;
 pub type SubmissionsVec<M> = ArrayVec<Submission<M>, MAX_SUBMISSIONS>;
 
+pub const MAX_PAYMENT_TOKENS: usize = 10;
+pub type WithdrawableVec<M> = ArrayVec<TokenAmountPair<M>, MAX_PAYMENT_TOKENS>;
+
 #[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
 pub struct RoundDetails<M: ManagedTypeApi> {
     pub submissions: SubmissionsVec<M>,
     pub max_submissions: u64,
     pub min_submissions: u64,
     pub timeout: u64,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
     pub payment_amount: BigUint<M>,
 }
 
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct TokenAmountPair<M: ManagedTypeApi> {
+    pub token_id: EgldOrEsdtTokenIdentifier<M>,
+    pub amount: BigUint<M>,
+}
+
 #[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
 pub struct OracleStatus<M: ManagedTypeApi> {
-    pub withdrawable: BigUint<M>,
+    pub withdrawable: WithdrawableVec<M>,
     pub starting_round: u64,
     pub ending_round: u64,
     pub last_reported_round: u64,
@@ -49,6 +59,7 @@ pub struct OracleRoundState<M: ManagedTypeApi> {
     pub timeout: u64,
     pub available_funds: BigUint<M>,
     pub oracle_count: u64,
+    pub payment_token: EgldOrEsdtTokenIdentifier<M>,
     pub payment_amount: BigUint<M>,
 }
 
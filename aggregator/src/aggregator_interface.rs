@@ -5,10 +5,76 @@ elrond_wasm::derive_imports!();
 
 pub const MAX_SUBMISSION_VALUES: usize = 100;
 pub type SingleSubmissionValuesVec<M> = ArrayVec<BigUint<M>, MAX_SUBMISSION_VALUES>;
+pub type FieldTypesVec = ArrayVec<FieldType, MAX_SUBMISSION_VALUES>;
 
 pub const MAX_DESCRIPTION_LEN: usize = 50;
 pub type DescriptionVec = ArrayVec<u8, MAX_DESCRIPTION_LEN>;
 
+/// Signed values are submitted with this offset added, so that they can be ordered as unsigned values
+pub const SIGNED_VALUE_OFFSET_BITS: usize = 127;
+pub const BYTES_HASH_MAX_BITS: usize = 256;
+
+/// How a submission value at a given position is to be interpreted
+#[derive(
+    NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Debug, Clone, Copy,
+)]
+pub enum FieldType {
+    Unsigned,
+    /// Encoded as `value + 2^127`, must be below `2^128`
+    Signed,
+    /// Either 0 or 1
+    Boolean,
+    /// An unsigned value with the given number of decimals
+    FixedPoint(u8),
+    /// A hash of at most 32 bytes, as a big endian unsigned value
+    BytesHash,
+}
+
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Debug, Clone)]
+pub struct FieldSchema<M: ManagedTypeApi> {
+    pub name: ManagedBuffer<M>,
+    pub field_type: FieldType,
+}
+
+impl FieldType {
+    pub fn is_valid<M: ManagedTypeApi>(&self, value: &BigUint<M>) -> bool {
+        match self {
+            FieldType::Unsigned | FieldType::FixedPoint(_) => true,
+            FieldType::Signed => value < &power_of_two(SIGNED_VALUE_OFFSET_BITS + 1),
+            FieldType::Boolean => value <= &BigUint::from(1u32),
+            FieldType::BytesHash => value < &power_of_two(BYTES_HASH_MAX_BITS),
+        }
+    }
+
+    /// Numeric values are aggregated by their median, the others by the value most oracles agree on
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            FieldType::Unsigned | FieldType::Signed | FieldType::FixedPoint(_)
+        )
+    }
+
+    /// The size of a numeric value, checked against the submission limits and relative to which
+    /// the basis points deviation is measured. Signed values are measured by their absolute value.
+    pub fn magnitude<M: ManagedTypeApi>(&self, value: &BigUint<M>) -> Option<BigUint<M>> {
+        match self {
+            FieldType::Unsigned | FieldType::FixedPoint(_) => Some(value.clone()),
+            FieldType::Signed => Some(decode_signed(value).magnitude()),
+            FieldType::Boolean | FieldType::BytesHash => None,
+        }
+    }
+}
+
+/// Removes the offset from a value of a `FieldType::Signed` field
+pub fn decode_signed<M: ManagedTypeApi>(value: &BigUint<M>) -> BigInt<M> {
+    let offset = power_of_two(SIGNED_VALUE_OFFSET_BITS);
+    BigInt::from(value.clone()) - BigInt::from(offset)
+}
+
+fn power_of_two<M: ManagedTypeApi>(bits: usize) -> BigUint<M> {
+    BigUint::from(2u32).pow(bits as u32)
+}
+
 #[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Debug, Clone)]
 pub struct Submission<M: ManagedTypeApi> {
     pub values: SingleSubmissionValuesVec<M>,
@@ -23,4 +89,6 @@ pub struct Round<M: ManagedTypeApi> {
     pub started_at: u64,
     pub updated_at: u64,
     pub answered_in_round: u64,
+    /// Only set when the round is read through a view, while the aggregator is paused
+    pub paused: bool,
 }
@@ -0,0 +1,66 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+use crate::aggregator_interface::{FieldType, Submission};
+
+pub const BASIS_POINTS: u64 = 10_000;
+
+/// The maximum change allowed between the latest confirmed answer and a new one
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Debug, Clone)]
+pub enum AnswerDeviationLimit<M: ManagedTypeApi> {
+    Unlimited,
+    Absolute(BigUint<M>),
+    BasisPoints(u64),
+}
+
+/// An answer which exceeded the deviation limit and awaits confirmation
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct PendingAnswer<M: ManagedTypeApi> {
+    pub round_id: u64,
+    pub answer: Submission<M>,
+}
+
+impl<M: ManagedTypeApi> AnswerDeviationLimit<M> {
+    /// Checks every numeric value of the new answer against the value at the same position in the
+    /// previous one. Booleans and hashes are not compared.
+    pub fn is_deviation_exceeded(
+        &self,
+        field_types: &[FieldType],
+        previous: &Submission<M>,
+        new: &Submission<M>,
+    ) -> bool {
+        previous
+            .values
+            .iter()
+            .zip(new.values.iter())
+            .zip(field_types.iter())
+            .any(|((previous_value, new_value), field_type)| {
+                match field_type.magnitude(previous_value) {
+                    Some(reference) => self.is_change_exceeded(
+                        &absolute_difference(previous_value, new_value),
+                        &reference,
+                    ),
+                    None => false,
+                }
+            })
+    }
+
+    /// The basis points limit is relative to `reference`, the size of the previous value
+    fn is_change_exceeded(&self, change: &BigUint<M>, reference: &BigUint<M>) -> bool {
+        match self {
+            AnswerDeviationLimit::Unlimited => false,
+            AnswerDeviationLimit::Absolute(max_change) => change > max_change,
+            AnswerDeviationLimit::BasisPoints(max_change) => {
+                change * &BigUint::from(BASIS_POINTS) > reference * &BigUint::from(*max_change)
+            }
+        }
+    }
+}
+
+/// Signed values share the same offset, so the difference of their encodings is their difference
+fn absolute_difference<M: ManagedTypeApi>(a: &BigUint<M>, b: &BigUint<M>) -> BigUint<M> {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
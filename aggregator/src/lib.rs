@@ -4,23 +4,36 @@
 elrond_wasm::imports!();
 mod aggregator_data;
 pub mod aggregator_interface;
+pub mod answer_bounds;
 pub mod median;
 
 use core::borrow::Borrow;
 
 use aggregator_data::{
     AddressAmountPair, Funds, OracleRoundState, OracleStatus, Requester, RoundDetails, Submission,
+    TokenAmountPair, MAX_PAYMENT_TOKENS,
 };
-use aggregator_interface::{DescriptionVec, Round, SingleSubmissionValuesVec};
+use aggregator_interface::{
+    DescriptionVec, FieldSchema, FieldTypesVec, Round, SingleSubmissionValuesVec,
+    MAX_SUBMISSION_VALUES,
+};
+use answer_bounds::{AnswerDeviationLimit, PendingAnswer};
 
 const RESERVE_ROUNDS: u64 = 2;
 const ROUND_MAX: u64 = u64::MAX;
+pub const PAYMENT_MULTIPLIER_PRECISION: u64 = 10_000;
+pub const MAX_PAYMENT_MULTIPLIER: u64 = 10 * PAYMENT_MULTIPLIER_PRECISION;
+static PAUSED_ERROR_MSG: &[u8] = b"Contract is paused";
 
 #[elrond_wasm::contract]
-pub trait Aggregator {
+pub trait Aggregator: elrond_wasm_modules::pause::PauseModule {
+    /// The token in which future rounds are paid
     #[storage_mapper("token_id")]
     fn token_id(&self) -> SingleValueMapper<EgldOrEsdtTokenIdentifier>;
 
+    #[storage_mapper("accepted_tokens")]
+    fn accepted_tokens(&self) -> SetMapper<EgldOrEsdtTokenIdentifier>;
+
     // Round related params
     #[storage_mapper("payment_amount")]
     fn payment_amount(&self) -> SingleValueMapper<BigUint>;
@@ -62,10 +75,13 @@ pub trait Aggregator {
     fn requesters(&self) -> MapMapper<ManagedAddress, Requester>;
 
     #[storage_mapper("recorded_funds")]
-    fn recorded_funds(&self) -> SingleValueMapper<Funds<Self::Api>>;
+    fn recorded_funds(
+        &self,
+        token_id: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<Funds<Self::Api>>;
 
     #[storage_mapper("deposits")]
-    fn deposits(&self) -> MapMapper<ManagedAddress, BigUint>;
+    fn deposits(&self, token_id: &EgldOrEsdtTokenIdentifier) -> MapMapper<ManagedAddress, BigUint>;
 
     #[storage_mapper("decimals")]
     fn decimals(&self) -> SingleValueMapper<u8>;
@@ -73,8 +89,28 @@ pub trait Aggregator {
     #[storage_mapper("description")]
     fn description(&self) -> SingleValueMapper<DescriptionVec>;
 
-    #[storage_mapper("values_count")]
-    fn values_count(&self) -> SingleValueMapper<usize>;
+    #[storage_mapper("feed_schema")]
+    fn feed_schema(&self) -> VecMapper<FieldSchema<Self::Api>>;
+
+    /// Per-oracle payment multiplier, in basis points of the round payment.
+    /// Oracles without an entry are paid the base round payment.
+    #[storage_mapper("payment_multipliers")]
+    fn payment_multipliers(&self) -> MapMapper<ManagedAddress, u64>;
+
+    /// Basis points deducted from the payment for each submission
+    /// already recorded in the round when an oracle submits.
+    #[storage_mapper("late_submission_decay")]
+    fn late_submission_decay(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("max_answer_deviation")]
+    fn max_answer_deviation(&self) -> SingleValueMapper<AnswerDeviationLimit<Self::Api>>;
+
+    #[storage_mapper("pending_answer")]
+    fn pending_answer(&self) -> SingleValueMapper<PendingAnswer<Self::Api>>;
+
+    #[view(getGuardian)]
+    #[storage_mapper("guardian")]
+    fn guardian(&self) -> SingleValueMapper<ManagedAddress>;
 
     #[init]
     fn init(
@@ -86,20 +122,23 @@ pub trait Aggregator {
         max_submission_value: BigUint,
         decimals: u8,
         description: DescriptionVec,
-        values_count: usize,
+        schema: MultiValueEncoded<FieldSchema<Self::Api>>,
     ) {
+        self.add_accepted_token(token_id.clone());
         self.token_id().set(&token_id);
-        self.recorded_funds().set(&Funds {
-            available: BigUint::zero(),
-            allocated: BigUint::zero(),
-        });
 
         self.update_future_rounds_internal(payment_amount, 0, 0, 0, timeout);
         self.min_submission_value().set(&min_submission_value);
         self.max_submission_value().set(&max_submission_value);
         self.decimals().set(&decimals);
         self.description().set(&description);
-        self.values_count().set(&values_count);
+        require!(
+            !schema.is_empty() && schema.len() <= MAX_SUBMISSION_VALUES,
+            "invalid number of schema fields"
+        );
+        for field in schema {
+            self.feed_schema().push(&field);
+        }
         self.initialize_new_round(0);
     }
 
@@ -107,36 +146,124 @@ pub trait Aggregator {
     #[payable("*")]
     fn add_funds(&self) {
         let (token, payment) = self.call_value().egld_or_single_fungible_esdt();
-        require!(token == self.token_id().get(), "Wrong token type");
-        self.recorded_funds()
+        require!(self.accepted_tokens().contains(&token), "Wrong token type");
+        self.recorded_funds(&token)
             .update(|recorded_funds| recorded_funds.available += &payment);
         let caller = &self.blockchain().get_caller();
-        let deposit = self.get_deposit(caller) + payment;
-        self.set_deposit(caller, &deposit);
+        let deposit = self.get_deposit(&token, caller) + payment;
+        self.set_deposit(&token, caller, &deposit);
+    }
+
+    #[only_owner]
+    #[endpoint(addAcceptedToken)]
+    fn add_accepted_token(&self, token_id: EgldOrEsdtTokenIdentifier) {
+        require!(
+            self.accepted_tokens().len() < MAX_PAYMENT_TOKENS,
+            "too many accepted tokens"
+        );
+        require!(
+            self.accepted_tokens().insert(token_id.clone()),
+            "token already accepted"
+        );
+        if self.recorded_funds(&token_id).is_empty() {
+            self.recorded_funds(&token_id).set(&Funds {
+                available: BigUint::zero(),
+                allocated: BigUint::zero(),
+            });
+        }
+    }
+
+    /// Stops accepting new funds in the given token.
+    /// Existing deposits and oracle payments in that token can still be withdrawn.
+    #[only_owner]
+    #[endpoint(removeAcceptedToken)]
+    fn remove_accepted_token(&self, token_id: EgldOrEsdtTokenIdentifier) {
+        require!(
+            token_id != self.token_id().get(),
+            "cannot remove the payment token"
+        );
+        require!(
+            self.accepted_tokens().remove(&token_id),
+            "token not accepted"
+        );
+    }
+
+    /// Rounds started from now on are paid in the given token.
+    #[only_owner]
+    #[endpoint(setPaymentToken)]
+    fn set_payment_token(&self, token_id: EgldOrEsdtTokenIdentifier) {
+        require!(
+            self.accepted_tokens().contains(&token_id),
+            "token not accepted"
+        );
+        self.token_id().set(&token_id);
+        self.require_reserve_for_payment(&self.payment_amount().get());
+    }
+
+    #[view(getAcceptedTokens)]
+    fn get_accepted_tokens(&self) -> MultiValueEncoded<EgldOrEsdtTokenIdentifier> {
+        let mut tokens = MultiValueEncoded::new();
+        for token_id in self.accepted_tokens().iter() {
+            tokens.push(token_id);
+        }
+
+        tokens
     }
 
-    fn get_deposit(&self, address: &ManagedAddress) -> BigUint {
-        self.deposits()
+    #[view(getPaymentToken)]
+    fn get_payment_token(&self) -> EgldOrEsdtTokenIdentifier {
+        self.token_id().get()
+    }
+
+    fn get_deposit(
+        &self,
+        token_id: &EgldOrEsdtTokenIdentifier,
+        address: &ManagedAddress,
+    ) -> BigUint {
+        self.deposits(token_id)
             .get(address)
             .unwrap_or_else(|| BigUint::zero())
     }
 
-    fn set_deposit(&self, address: &ManagedAddress, amount: &BigUint) {
+    fn set_deposit(
+        &self,
+        token_id: &EgldOrEsdtTokenIdentifier,
+        address: &ManagedAddress,
+        amount: &BigUint,
+    ) {
         if amount == &BigUint::zero() {
-            self.deposits().remove(address);
+            self.deposits(token_id).remove(address);
         } else {
-            self.deposits().insert(address.clone(), amount.clone());
+            self.deposits(token_id)
+                .insert(address.clone(), amount.clone());
         }
     }
 
+    fn token_or_payment_token(
+        &self,
+        token_id: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) -> EgldOrEsdtTokenIdentifier {
+        token_id
+            .into_option()
+            .unwrap_or_else(|| self.token_id().get())
+    }
+
     fn validate_submission_limits(&self, submission_values: &SingleSubmissionValuesVec<Self::Api>) {
-        for value in submission_values.iter() {
+        for (value, field) in submission_values.iter().zip(self.feed_schema().iter()) {
             require!(
-                value >= &self.min_submission_value().get(),
+                field.field_type.is_valid(value),
+                "value does not match the field type"
+            );
+            let magnitude = match field.field_type.magnitude(value) {
+                Some(magnitude) => magnitude,
+                None => continue,
+            };
+            require!(
+                magnitude >= self.min_submission_value().get(),
                 "value below min_submission_value"
             );
             require!(
-                value <= &self.max_submission_value().get(),
+                magnitude <= self.max_submission_value().get(),
                 "value above max_submission_value"
             );
         }
@@ -144,8 +271,9 @@ pub trait Aggregator {
 
     #[endpoint(submit)]
     fn submit(&self, round_id: u64, submission_values: MultiValueEncoded<BigUint>) {
+        self.require_not_paused();
         require!(
-            submission_values.len() == self.values_count().get(),
+            submission_values.len() == self.feed_schema().len(),
             "incorrect number of values in submission"
         );
         if let SCResult::Err(err) =
@@ -180,6 +308,7 @@ pub trait Aggregator {
     ) {
         for oracle in &removed {
             self.oracles().remove(&oracle);
+            self.payment_multipliers().remove(&oracle);
         }
 
         require!(
@@ -238,12 +367,7 @@ pub trait Aggregator {
             "delay cannot exceed total"
         );
 
-        let recorded_funds = self.recorded_funds().get();
-
-        require!(
-            recorded_funds.available >= self.required_reserve(&payment_amount),
-            "insufficient funds for payment"
-        );
+        self.require_reserve_for_payment(&payment_amount);
 
         if oracle_count > 0 {
             require!(min_submissions > 0, "min must be greater than 0");
@@ -255,14 +379,144 @@ pub trait Aggregator {
         self.timeout().set(&timeout);
     }
 
+    #[only_owner]
+    #[endpoint(setPaymentMultiplier)]
+    fn set_payment_multiplier(&self, oracle: ManagedAddress, multiplier: u64) {
+        require!(self.oracle_enabled(&oracle), "No oracle at given address");
+        require!(
+            multiplier <= MAX_PAYMENT_MULTIPLIER,
+            "multiplier cannot exceed 10x the payment"
+        );
+        if multiplier == PAYMENT_MULTIPLIER_PRECISION {
+            self.payment_multipliers().remove(&oracle);
+        } else {
+            self.payment_multipliers().insert(oracle, multiplier);
+        }
+        self.require_reserve_for_payment(&self.payment_amount().get());
+    }
+
+    #[only_owner]
+    #[endpoint(setLateSubmissionDecay)]
+    fn set_late_submission_decay(&self, decay: u64) {
+        require!(
+            decay <= PAYMENT_MULTIPLIER_PRECISION,
+            "decay cannot exceed the full payment"
+        );
+        self.late_submission_decay().set(decay);
+    }
+
+    /// Answers which change more than the given limit compared to the latest confirmed answer
+    /// are held until a following round or the guardian confirms them.
+    #[only_owner]
+    #[endpoint(setMaxAnswerDeviation)]
+    fn set_max_answer_deviation(&self, limit: AnswerDeviationLimit<Self::Api>) {
+        if let AnswerDeviationLimit::Unlimited = limit {
+            self.pending_answer().clear();
+        }
+        self.max_answer_deviation().set(&limit);
+    }
+
+    #[only_owner]
+    #[endpoint(setGuardian)]
+    fn set_guardian(&self, guardian: ManagedAddress) {
+        self.guardian().set(&guardian);
+    }
+
+    #[endpoint(confirmPendingAnswer)]
+    fn confirm_pending_answer(&self) {
+        self.only_guardian();
+        require!(!self.pending_answer().is_empty(), "no pending answer");
+
+        let pending = self.pending_answer().get();
+        self.pending_answer().clear();
+        self.set_round_answer(pending.round_id, Some(pending.answer));
+    }
+
+    #[view(getMaxAnswerDeviation)]
+    fn get_max_answer_deviation(&self) -> AnswerDeviationLimit<Self::Api> {
+        if self.max_answer_deviation().is_empty() {
+            AnswerDeviationLimit::Unlimited
+        } else {
+            self.max_answer_deviation().get()
+        }
+    }
+
+    #[view(pendingAnswer)]
+    fn get_pending_answer(&self) -> OptionalValue<PendingAnswer<Self::Api>> {
+        if self.pending_answer().is_empty() {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(self.pending_answer().get())
+        }
+    }
+
+    /// Lets the guardian stop submissions and new rounds.
+    /// Only the owner can unpause the contract.
+    #[endpoint(emergencyPause)]
+    fn emergency_pause(&self) {
+        let caller = self.blockchain().get_caller();
+        if caller != self.blockchain().get_owner_address() {
+            self.only_guardian();
+        }
+        self.set_paused(true);
+    }
+
+    fn require_not_paused(&self) {
+        require!(self.not_paused(), PAUSED_ERROR_MSG);
+    }
+
+    fn only_guardian(&self) {
+        require!(
+            !self.guardian().is_empty() && self.guardian().get() == self.blockchain().get_caller(),
+            "only callable by guardian"
+        );
+    }
+
+    #[view(getPaymentMultiplier)]
+    fn get_payment_multiplier(&self, oracle: &ManagedAddress) -> u64 {
+        self.payment_multipliers()
+            .get(oracle)
+            .unwrap_or(PAYMENT_MULTIPLIER_PRECISION)
+    }
+
+    /// The payment an oracle receives for a round with the given base payment,
+    /// when its submission is the `submission_index`-th one in that round.
+    fn oracle_payment(
+        &self,
+        payment: &BigUint,
+        oracle: &ManagedAddress,
+        submission_index: usize,
+    ) -> BigUint {
+        let decay = self.late_submission_decay().get() * submission_index as u64;
+        let position_factor = PAYMENT_MULTIPLIER_PRECISION.saturating_sub(decay);
+        let precision = BigUint::from(PAYMENT_MULTIPLIER_PRECISION);
+        payment * &BigUint::from(self.get_payment_multiplier(oracle)) / &precision
+            * BigUint::from(position_factor)
+            / precision
+    }
+
     #[view(allocatedFunds)]
-    fn allocated_funds(&self) -> BigUint {
-        self.recorded_funds().get().allocated
+    fn allocated_funds(&self, token_id: OptionalValue<EgldOrEsdtTokenIdentifier>) -> BigUint {
+        self.get_recorded_funds(&self.token_or_payment_token(token_id))
+            .allocated
     }
 
     #[view(availableFunds)]
-    fn available_funds(&self) -> BigUint {
-        self.recorded_funds().get().available
+    fn available_funds(&self, token_id: OptionalValue<EgldOrEsdtTokenIdentifier>) -> BigUint {
+        self.get_recorded_funds(&self.token_or_payment_token(token_id))
+            .available
+    }
+
+    fn get_recorded_funds(&self, token_id: &EgldOrEsdtTokenIdentifier) -> Funds<Self::Api> {
+        let recorded_funds = self.recorded_funds(token_id);
+        if recorded_funds.is_empty() {
+            Funds {
+                available: BigUint::zero(),
+                allocated: BigUint::zero(),
+            }
+        } else {
+            recorded_funds.get()
+        }
     }
 
     #[view(oracleCount)]
@@ -272,7 +526,13 @@ pub trait Aggregator {
 
     #[view(getRoundData)]
     fn get_round_data(&self, round_id: u64) -> OptionalValue<Round<Self::Api>> {
-        self.rounds().get(&round_id).into()
+        self.rounds()
+            .get(&round_id)
+            .map(|mut round| {
+                round.paused = self.is_paused();
+                round
+            })
+            .into()
     }
 
     #[view(latestRoundData)]
@@ -281,12 +541,24 @@ pub trait Aggregator {
     }
 
     #[view(withdrawablePayment)]
-    fn withdrawable_payment(&self, oracle: ManagedAddress) -> BigUint {
-        self.get_oracle_status_result(&oracle).withdrawable
+    fn withdrawable_payment(
+        &self,
+        oracle: ManagedAddress,
+        token_id: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) -> BigUint {
+        let token_id = self.token_or_payment_token(token_id);
+        self.get_withdrawable(&self.get_oracle_status_result(&oracle), &token_id)
     }
 
     #[endpoint(withdrawPayment)]
-    fn withdraw_payment(&self, oracle: ManagedAddress, recipient: ManagedAddress, amount: BigUint) {
+    fn withdraw_payment(
+        &self,
+        oracle: ManagedAddress,
+        recipient: ManagedAddress,
+        amount: BigUint,
+        token_id: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) {
+        let token_id = self.token_or_payment_token(token_id);
         let mut oracle_status = self.get_oracle_status_result(&oracle);
         require!(
             oracle_status.admin == self.blockchain().get_caller(),
@@ -294,41 +566,98 @@ pub trait Aggregator {
         );
 
         require!(
-            oracle_status.withdrawable >= amount,
+            self.get_withdrawable(&oracle_status, &token_id) >= amount,
             "insufficient withdrawable funds"
         );
 
-        self.recorded_funds()
+        self.recorded_funds(&token_id)
             .update(|recorded_funds| recorded_funds.allocated -= &amount);
-        oracle_status.withdrawable -= &amount;
+        self.debit_withdrawable(&mut oracle_status, &token_id, &amount);
         self.oracles().insert(oracle, oracle_status);
 
-        self.send()
-            .direct(&recipient, &self.token_id().get(), 0, &amount);
+        self.send().direct(&recipient, &token_id, 0, &amount);
     }
 
     #[view(withdrawableAddedFunds)]
-    fn withdrawable_added_funds(&self) -> BigUint {
-        self.get_deposit(&self.blockchain().get_caller())
+    fn withdrawable_added_funds(
+        &self,
+        token_id: OptionalValue<EgldOrEsdtTokenIdentifier>,
+    ) -> BigUint {
+        let token_id = self.token_or_payment_token(token_id);
+        self.get_deposit(&token_id, &self.blockchain().get_caller())
     }
 
     #[endpoint(withdrawFunds)]
-    fn withdraw_funds(&self, amount: BigUint) {
-        let recorded_funds = self.recorded_funds().get();
+    fn withdraw_funds(&self, amount: BigUint, token_id: OptionalValue<EgldOrEsdtTokenIdentifier>) {
+        let token_id = self.token_or_payment_token(token_id);
+        let recorded_funds = self.get_recorded_funds(&token_id);
         let caller = &self.blockchain().get_caller();
-        let deposit = self.get_deposit(caller);
+        let deposit = self.get_deposit(&token_id, caller);
         require!(amount <= deposit, "Insufficient funds to withdraw");
         require!(
-            recorded_funds.available - self.required_reserve(&self.payment_amount().get())
-                >= amount,
+            recorded_funds.available - self.required_reserve_for_token(&token_id) >= amount,
             "insufficient reserve funds"
         );
-        self.recorded_funds()
+        self.recorded_funds(&token_id)
             .update(|recorded_funds| recorded_funds.available -= &amount);
         let remaining = &deposit - &amount;
-        self.set_deposit(caller, &remaining);
-        self.send()
-            .direct(caller, &self.token_id().get(), 0, &amount);
+        self.set_deposit(&token_id, caller, &remaining);
+        self.send().direct(caller, &token_id, 0, &amount);
+    }
+
+    fn get_withdrawable(
+        &self,
+        oracle_status: &OracleStatus<Self::Api>,
+        token_id: &EgldOrEsdtTokenIdentifier,
+    ) -> BigUint {
+        oracle_status
+            .withdrawable
+            .iter()
+            .find(|pair| &pair.token_id == token_id)
+            .map_or_else(|| BigUint::zero(), |pair| pair.amount.clone())
+    }
+
+    fn credit_withdrawable(
+        &self,
+        oracle_status: &mut OracleStatus<Self::Api>,
+        token_id: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    ) {
+        if let Some(pair) = oracle_status
+            .withdrawable
+            .iter_mut()
+            .find(|pair| &pair.token_id == token_id)
+        {
+            pair.amount += amount;
+            return;
+        }
+        require!(
+            oracle_status
+                .withdrawable
+                .try_push(TokenAmountPair {
+                    token_id: token_id.clone(),
+                    amount: amount.clone(),
+                })
+                .is_ok(),
+            "too many withdrawable tokens"
+        );
+    }
+
+    fn debit_withdrawable(
+        &self,
+        oracle_status: &mut OracleStatus<Self::Api>,
+        token_id: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    ) {
+        let index = oracle_status
+            .withdrawable
+            .iter()
+            .position(|pair| &pair.token_id == token_id)
+            .unwrap_or_else(|| sc_panic!("insufficient withdrawable funds"));
+        oracle_status.withdrawable[index].amount -= amount;
+        if oracle_status.withdrawable[index].amount == 0u32 {
+            oracle_status.withdrawable.remove(index);
+        }
     }
 
     #[view(getAdmin)]
@@ -362,6 +691,7 @@ pub trait Aggregator {
 
     #[endpoint(requestNewRound)]
     fn request_new_round(&self) -> u64 {
+        self.require_not_paused();
         let requester_option = self.requesters().get(&self.blockchain().get_caller());
         require!(
             requester_option.map_or_else(|| false, |requester| requester.authorized),
@@ -413,7 +743,18 @@ pub trait Aggregator {
         let round = self.get_round(queried_round_id);
         let details = self.get_round_details(queried_round_id);
         let oracle_status = self.get_oracle_status_result(&oracle);
-        let recorded_funds = self.recorded_funds().get();
+        let (payment_token, payment_amount) = if round.started_at > 0 {
+            (
+                details.payment_token.clone(),
+                self.oracle_payment(&details.payment_amount, &oracle, details.submissions.len()),
+            )
+        } else {
+            (
+                self.token_id().get(),
+                self.oracle_payment(&self.payment_amount().get(), &oracle, 0),
+            )
+        };
+        let recorded_funds = self.get_recorded_funds(&payment_token);
 
         OracleRoundState {
             eligible_to_submit,
@@ -423,11 +764,8 @@ pub trait Aggregator {
             timeout: details.timeout,
             available_funds: recorded_funds.available,
             oracle_count: self.oracle_count(),
-            payment_amount: if round.started_at > 0 {
-                details.payment_amount
-            } else {
-                self.payment_amount().get()
-            },
+            payment_token,
+            payment_amount,
         }
     }
 
@@ -447,6 +785,7 @@ pub trait Aggregator {
                 started_at: self.blockchain().get_block_timestamp(),
                 updated_at: self.blockchain().get_block_timestamp(),
                 answered_in_round: 0,
+                paused: false,
             },
         );
         self.details().insert(
@@ -456,6 +795,7 @@ pub trait Aggregator {
                 max_submissions: self.max_submission_count().get(),
                 min_submissions: self.min_submission_count().get(),
                 timeout: self.timeout().get(),
+                payment_token: self.token_id().get(),
                 payment_amount: self.payment_amount().get(),
             },
         );
@@ -547,19 +887,26 @@ pub trait Aggregator {
         let mut eligible_to_submit: bool;
         let round: Round<Self::Api>;
         let round_id: u64;
+        let payment_token: EgldOrEsdtTokenIdentifier;
         let payment_amount: BigUint;
         if self.supersedable(reporting_round_id) && should_supersede {
             round_id = reporting_round_id + 1;
             round = self.get_round(round_id);
 
-            payment_amount = self.payment_amount().get();
+            payment_token = self.token_id().get();
+            payment_amount = self.oracle_payment(&self.payment_amount().get(), oracle, 0);
             eligible_to_submit = self.delayed(&oracle, round_id);
         } else {
             round_id = reporting_round_id;
             round = self.get_round(round_id);
 
             let round_details = self.get_round_details(round_id);
-            payment_amount = round_details.payment_amount;
+            payment_token = round_details.payment_token.clone();
+            payment_amount = self.oracle_payment(
+                &round_details.payment_amount,
+                oracle,
+                round_details.submissions.len(),
+            );
             eligible_to_submit = self.accepting_submissions(round_id);
         }
 
@@ -567,7 +914,7 @@ pub trait Aggregator {
             eligible_to_submit = false;
         }
 
-        let recorded_funds = self.recorded_funds().get();
+        let recorded_funds = self.get_recorded_funds(&payment_token);
         let round_details = self.get_round_details(round_id);
 
         OracleRoundState {
@@ -578,6 +925,7 @@ pub trait Aggregator {
             timeout: round_details.timeout,
             available_funds: recorded_funds.available,
             oracle_count: self.oracle_count(),
+            payment_token,
             payment_amount,
         }
     }
@@ -588,23 +936,94 @@ pub trait Aggregator {
             return;
         }
 
-        match median::calculate_submission_median(details.submissions) {
+        match median::calculate_submission_median(details.submissions, &self.feed_field_types()) {
             Result::Ok(new_answer) => {
-                let mut round = self.get_round(round_id);
-                round.answer = new_answer;
-                round.updated_at = self.blockchain().get_block_timestamp();
-                round.answered_in_round = round_id;
-                self.rounds().insert(round_id, round);
-                self.latest_round_id().set(&round_id);
+                if let Some(answer) = &new_answer {
+                    if self.hold_for_confirmation(round_id, answer) {
+                        return;
+                    }
+                }
+                self.set_round_answer(round_id, new_answer);
             }
             Result::Err(error_message) => sc_panic!(error_message.as_bytes()),
         }
     }
 
-    fn subtract_amount_from_deposits(&self, amount: &BigUint) {
+    fn set_round_answer(&self, round_id: u64, answer: Option<Submission<Self::Api>>) {
+        let mut round = self.get_round(round_id);
+        round.answer = answer;
+        round.updated_at = self.blockchain().get_block_timestamp();
+        round.answered_in_round = round_id;
+        self.rounds().insert(round_id, round);
+        if round_id > self.latest_round_id().get() {
+            self.latest_round_id().set(&round_id);
+        }
+    }
+
+    /// Returns true if the answer was put on hold, because it deviates too much
+    /// from both the latest confirmed answer and the currently pending one.
+    /// An answer close to the pending one confirms the pending answer first.
+    fn hold_for_confirmation(&self, round_id: u64, answer: &Submission<Self::Api>) -> bool {
+        let limit = self.get_max_answer_deviation();
+        if limit == AnswerDeviationLimit::Unlimited {
+            return false;
+        }
+
+        if !self.pending_answer().is_empty() {
+            let pending = self.pending_answer().get();
+            // a pending answer of the same round is replaced by the recalculated one
+            if pending.round_id != round_id
+                && !self.is_deviation_exceeded(&limit, &pending.answer, answer)
+            {
+                self.pending_answer().clear();
+                self.set_round_answer(pending.round_id, Some(pending.answer));
+                return false;
+            }
+        }
+
+        let latest_answer = self
+            .rounds()
+            .get(&self.latest_round_id().get())
+            .and_then(|round| round.answer);
+        match latest_answer {
+            Some(latest_answer) if self.is_deviation_exceeded(&limit, &latest_answer, answer) => {
+                self.pending_answer().set(&PendingAnswer {
+                    round_id,
+                    answer: answer.clone(),
+                });
+                true
+            }
+            _ => {
+                self.pending_answer().clear();
+                false
+            }
+        }
+    }
+
+    fn is_deviation_exceeded(
+        &self,
+        limit: &AnswerDeviationLimit<Self::Api>,
+        previous: &Submission<Self::Api>,
+        new: &Submission<Self::Api>,
+    ) -> bool {
+        limit.is_deviation_exceeded(&self.feed_field_types(), previous, new)
+    }
+
+    fn feed_field_types(&self) -> FieldTypesVec {
+        self.feed_schema()
+            .iter()
+            .map(|field| field.field_type)
+            .collect()
+    }
+
+    fn subtract_amount_from_deposits(
+        &self,
+        token_id: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    ) {
         let mut remaining = amount.clone();
         let mut final_amounts = ManagedVec::<Self::Api, AddressAmountPair<Self::Api>>::new();
-        for (account, deposit) in self.deposits().iter() {
+        for (account, deposit) in self.deposits(token_id).iter() {
             if remaining == BigUint::zero() {
                 break;
             }
@@ -623,7 +1042,7 @@ pub trait Aggregator {
             }
         }
         for pair in &final_amounts {
-            self.set_deposit(&pair.address, &pair.amount);
+            self.set_deposit(token_id, &pair.address, &pair.amount);
         }
     }
 
@@ -632,14 +1051,18 @@ pub trait Aggregator {
         let oracle = self.blockchain().get_caller();
         let mut oracle_status = self.get_oracle_status_result(&oracle);
 
-        let payment = round_details.payment_amount;
-        self.recorded_funds().update(|recorded_funds| {
-            recorded_funds.available -= &payment;
-            recorded_funds.allocated += &payment;
-        });
-        self.subtract_amount_from_deposits(&payment);
-
-        oracle_status.withdrawable += &payment;
+        // the oracle's own submission was already recorded
+        let submission_index = round_details.submissions.len() - 1;
+        let payment_token = round_details.payment_token;
+        let payment = self.oracle_payment(&round_details.payment_amount, &oracle, submission_index);
+        self.recorded_funds(&payment_token)
+            .update(|recorded_funds| {
+                recorded_funds.available -= &payment;
+                recorded_funds.allocated += &payment;
+            });
+        self.subtract_amount_from_deposits(&payment_token, &payment);
+
+        self.credit_withdrawable(&mut oracle_status, &payment_token, &payment);
         self.oracles().insert(oracle, oracle_status);
     }
 
@@ -671,8 +1094,12 @@ pub trait Aggregator {
     fn timed_out(&self, round_id: u64) -> bool {
         let round = self.get_round(round_id);
         let started_at = round.started_at;
-        let details = self.get_round_details(round_id);
-        let round_timeout = details.timeout;
+        // the details of a round are deleted once it got all of its submissions,
+        // so a round without them did not time out
+        let round_timeout = self
+            .details()
+            .get(&round_id)
+            .map_or(0, |details| details.timeout);
 
         round_id == 0
             || (started_at > 0
@@ -697,9 +1124,37 @@ pub trait Aggregator {
         round_id + 1 == rr_id && round.updated_at == 0
     }
 
+    /// Funds needed to pay every oracle at its own multiplier for `RESERVE_ROUNDS` rounds.
+    /// The late submission decay is ignored, so this is an upper bound of the actual payouts.
     #[view(requiredReserve)]
     fn required_reserve(&self, payment: &BigUint) -> BigUint {
-        payment * &BigUint::from(self.oracle_count()) * BigUint::from(RESERVE_ROUNDS)
+        let mut round_payouts = BigUint::zero();
+        for oracle in self.oracles().keys() {
+            round_payouts += self.oracle_payment(payment, &oracle, 0);
+        }
+        round_payouts * BigUint::from(RESERVE_ROUNDS)
+    }
+
+    fn require_reserve_for_payment(&self, payment: &BigUint) {
+        require!(
+            self.get_recorded_funds(&self.token_id().get()).available
+                >= self.required_reserve(payment),
+            "insufficient funds for payment"
+        );
+    }
+
+    /// The reserve only covers the payment token of future rounds
+    /// and the token the currently reporting round is paid in.
+    fn required_reserve_for_token(&self, token_id: &EgldOrEsdtTokenIdentifier) -> BigUint {
+        let reporting_round_token = self
+            .details()
+            .get(&self.reporting_round_id().get())
+            .map(|details| details.payment_token);
+        if token_id == &self.token_id().get() || reporting_round_token.as_ref() == Some(token_id) {
+            self.required_reserve(&self.payment_amount().get())
+        } else {
+            BigUint::zero()
+        }
     }
 
     fn add_oracle(&self, oracle: &ManagedAddress, admin: &ManagedAddress) {
@@ -708,7 +1163,7 @@ pub trait Aggregator {
         self.oracles().insert(
             oracle.clone(),
             OracleStatus {
-                withdrawable: BigUint::zero(),
+                withdrawable: ArrayVec::new(),
                 starting_round: self.get_starting_round(oracle),
                 ending_round: ROUND_MAX,
                 last_reported_round: 0,
@@ -804,6 +1259,16 @@ pub trait Aggregator {
             .unwrap_or_else(|| sc_panic!("No requester has the given address"))
     }
 
+    #[view(feedSchema)]
+    fn get_feed_schema(&self) -> MultiValueEncoded<FieldSchema<Self::Api>> {
+        let mut schema = MultiValueEncoded::new();
+        for field in self.feed_schema().iter() {
+            schema.push(field);
+        }
+
+        schema
+    }
+
     #[view(getOracles)]
     fn get_oracles(&self) -> MultiValueEncoded<ManagedAddress> {
         let mut oracles = MultiValueEncoded::new();
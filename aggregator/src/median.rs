@@ -2,12 +2,15 @@ elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 use crate::{
     aggregator_data::SubmissionsVec,
-    aggregator_interface::{SingleSubmissionValuesVec, Submission},
+    aggregator_interface::{FieldType, SingleSubmissionValuesVec, Submission},
 };
 
-/// Calculates the median for each of the values in a Submission
+/// Calculates the median for each of the numeric values in a Submission.
+/// Booleans and hashes can't be averaged, so the lower middle value is taken instead,
+/// which is the value submitted by the majority of the oracles, if there is one.
 pub fn calculate_submission_median<M: ManagedTypeApi>(
     submissions: SubmissionsVec<M>,
+    field_types: &[FieldType],
 ) -> Result<Option<Submission<M>>, StaticSCError> {
     if submissions.is_empty() {
         return Result::Ok(None);
@@ -24,9 +27,14 @@ pub fn calculate_submission_median<M: ManagedTypeApi>(
     let mut new_submission = Submission {
         values: ArrayVec::new(),
     };
-    for values in iter {
-        let median = calculate(values.cloned().collect())?.unwrap().clone();
-        new_submission.values.push(median);
+    for (values, field_type) in iter.zip(field_types.iter()) {
+        let values = values.cloned().collect();
+        let median = if field_type.is_numeric() {
+            calculate(values)?
+        } else {
+            calculate_lower_middle(values)?
+        };
+        new_submission.values.push(median.unwrap());
     }
     Result::Ok(Some(new_submission))
 }
@@ -51,3 +59,17 @@ pub fn calculate<M: ManagedTypeApi>(
         Result::Ok(Some(median.clone()))
     }
 }
+
+/// Returns the sorted middle, or the lower of the two middle indexed items if the
+/// vector has an even number of elements. Unlike the median, this is always one of the values.
+pub fn calculate_lower_middle<M: ManagedTypeApi>(
+    mut list: SingleSubmissionValuesVec<M>,
+) -> Result<Option<BigUint<M>>, StaticSCError> {
+    if list.is_empty() {
+        return Result::Ok(None);
+    }
+    list.sort();
+    let middle_index = (list.len() - 1) / 2;
+    let middle = list.get(middle_index).ok_or("median invalid index")?;
+    Result::Ok(Some(middle.clone()))
+}
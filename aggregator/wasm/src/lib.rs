@@ -8,21 +8,40 @@ elrond_wasm_node::wasm_endpoints! {
     aggregator
     (
         acceptAdmin
+        addAcceptedToken
         addFunds
         allocatedFunds
         availableFunds
         changeOracles
+        confirmPendingAnswer
+        emergencyPause
+        feedSchema
+        getAcceptedTokens
         getAdmin
+        getGuardian
+        getMaxAnswerDeviation
         getOracles
+        getPaymentMultiplier
+        getPaymentToken
         getRoundData
+        isPaused
         latestRoundData
         oracleCount
         oracleRoundState
+        pause
+        pendingAnswer
+        removeAcceptedToken
         requestNewRound
         requiredReserve
+        setGuardian
+        setLateSubmissionDecay
+        setMaxAnswerDeviation
+        setPaymentMultiplier
+        setPaymentToken
         setRequesterPermissions
         submit
         transferAdmin
+        unpause
         updateFutureRounds
         withdrawFunds
         withdrawPayment
@@ -2,14 +2,13 @@
 
 mod client_data;
 use client_data::ClientData;
+use oracle_consumer::{OracleRequestBuilder, RequestId};
 
 elrond_wasm::imports!();
 
+/// An example consumer: requests data for a job and keeps the last answer received.
 #[elrond_wasm::contract]
-pub trait Client {
-    #[storage_mapper("oracle_address")]
-    fn oracle_address(&self) -> SingleValueMapper<ManagedAddress>;
-
+pub trait Client: oracle_consumer::OracleConsumerModule {
     #[view(getClientData)]
     fn get_client_data(&self) -> OptionalValue<ClientData<Self::Api>> {
         if self.client_data().is_empty() {
@@ -22,39 +21,68 @@ pub trait Client {
     #[storage_mapper("client_data")]
     fn client_data(&self) -> SingleValueMapper<ClientData<Self::Api>>;
 
-    #[storage_mapper("nonce")]
-    fn nonce(&self) -> SingleValueMapper<u64>;
-
-    #[proxy]
-    fn oracle_proxy(&self, to: ManagedAddress) -> oracle::Proxy<Self::Api>;
-
     #[init]
     fn init(&self, oracle_address: ManagedAddress) {
         self.oracle_address().set(oracle_address);
     }
 
+    /// Requests an answer from the oracle job with the given id, aggregated from
+    /// the answers of `threshold` nodes, passing the given key/value parameters as strings.
+    /// The payment is forwarded to the oracle as the request fee
     #[only_owner]
+    #[payable("*")]
     #[endpoint(sendRequest)]
-    fn send_request(&self) {
-        let callback_address = self.blockchain().get_sc_address();
-        let callback_method = ManagedBuffer::from(&b"reply"[..]);
-        let nonce = self.nonce().get();
-        self.nonce().update(|nonce| *nonce += 1);
-        let data = ManagedBuffer::new();
-        let mut oracle = self.oracle_proxy(self.oracle_address().get());
-
-        oracle
-            .request(callback_address, callback_method, nonce, data)
-            .async_call()
-            .call_and_exit();
+    fn send_request(
+        &self,
+        job_id: ManagedBuffer,
+        threshold: u32,
+        parameters: MultiValueEncoded<MultiValue2<ManagedBuffer, ManagedBuffer>>,
+    ) {
+        let (fee_token, fee) = self.call_value().egld_or_single_fungible_esdt();
+        let mut request =
+            OracleRequestBuilder::new(job_id, &b"storeAnswer"[..]).with_threshold(threshold);
+        for parameter in parameters {
+            let (key, value) = parameter.into_tuple();
+            request = request.add_string(key, &value);
+        }
+
+        self.send_oracle_request(request, fee_token, fee);
+    }
+
+    /// The handler of the answers, called by the oracle
+    #[endpoint(storeAnswer)]
+    fn store_answer(&self, request_id: RequestId<Self::Api>, nonce: u64, answer: ManagedBuffer) {
+        self.consume_oracle_answer(&request_id, nonce);
+        self.save_answer(nonce, answer);
+    }
+
+    /// Pulls from the oracle the answer of a pending request, by its nonce, whose delivery failed
+    #[only_owner]
+    #[endpoint(claimAnswer)]
+    fn claim_answer(&self, nonce: u64) {
+        let callback = Client::callbacks(self).claim_answer_callback(nonce);
+        self.claim_oracle_answer(nonce, callback);
+    }
+
+    #[callback]
+    fn claim_answer_callback(
+        &self,
+        #[call_result] result: ManagedAsyncCallResult<ManagedBuffer>,
+        nonce: u64,
+    ) {
+        match result {
+            ManagedAsyncCallResult::Ok(answer) => {
+                if self.consume_claimed_answer(nonce) {
+                    self.save_answer(nonce, answer);
+                }
+            }
+            ManagedAsyncCallResult::Err(error) => {
+                self.answer_claim_failed_event(nonce, &error.err_msg);
+            }
+        }
     }
 
-    #[endpoint(reply)]
-    fn reply(&self, nonce: u64, answer: ManagedBuffer) {
-        require!(
-            self.blockchain().get_caller() == self.oracle_address().get(),
-            "Only oracle can reply"
-        );
+    fn save_answer(&self, nonce: u64, answer: ManagedBuffer) {
         self.client_data().set(&ClientData { nonce, answer });
     }
 }
@@ -7,10 +7,13 @@
 elrond_wasm_node::wasm_endpoints! {
     client
     (
+        callBack
+        claimAnswer
         getClientData
-        reply
+        getOracleAddress
+        pendingRequests
         sendRequest
+        setOracleAddress
+        storeAnswer
     )
 }
-
-elrond_wasm_node::wasm_empty_callback! {}
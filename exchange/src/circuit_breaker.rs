@@ -0,0 +1,156 @@
+elrond_wasm::imports!();
+
+use crate::outcome::{Refund, RefundReason};
+use crate::quote::RouteHop;
+use crate::rate::Rate;
+
+/// Refuses swaps at rates which cannot be trusted: rates read from feeds older
+/// than the maximum age, from a round older than one already used, or which
+/// moved more than the maximum change since the last swap of the pair.
+/// The rate of the last swap is only compared to while it is younger than the
+/// maximum reference age, so that a pair left without swaps during a large
+/// price move resumes once the reference has aged out.
+#[elrond_wasm::module]
+pub trait CircuitBreakerModule {
+    /// Sets the maximum age, in seconds, of the price feeds used for swaps; zero disables the check
+    #[only_owner]
+    #[endpoint(setMaxPriceAge)]
+    fn set_max_price_age(&self, max_price_age: u64) {
+        self.max_price_age().set(max_price_age);
+    }
+
+    /// Sets the maximum change, in basis points, of a pair's rate since its last swap;
+    /// zero disables the check
+    #[only_owner]
+    #[endpoint(setMaxRateChange)]
+    fn set_max_rate_change(&self, max_rate_change_bps: u32) {
+        self.max_rate_change_bps().set(max_rate_change_bps);
+    }
+
+    /// Sets the time, in seconds, after which the rate of a pair's last swap is no
+    /// longer compared to; zero keeps it until the next swap
+    #[only_owner]
+    #[endpoint(setMaxReferenceAge)]
+    fn set_max_reference_age(&self, max_reference_age: u64) {
+        self.max_reference_age().set(max_reference_age);
+    }
+
+    /// Forgets the rate of the last swap of a pair, so that the next swap is not
+    /// compared to it; used to resume swaps after a legitimate large price move
+    #[only_owner]
+    #[endpoint(resetReferenceRate)]
+    fn reset_reference_rate(
+        &self,
+        source_token: EgldOrEsdtTokenIdentifier,
+        target_token: EgldOrEsdtTokenIdentifier,
+    ) {
+        self.reference_rate(&source_token, &target_token).clear();
+        self.reference_timestamp(&source_token, &target_token)
+            .clear();
+    }
+
+    fn check_price_safety(
+        &self,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+        rate: &Rate<Self::Api>,
+        route: &ManagedVec<RouteHop<Self::Api>>,
+    ) -> Result<(), Refund<Self::Api>> {
+        let max_price_age = self.max_price_age().get();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        for hop in route.iter() {
+            if max_price_age > 0 && current_timestamp.saturating_sub(hop.timestamp) > max_price_age
+            {
+                return Result::Err(Refund::new(
+                    RefundReason::StalePrice,
+                    self.hop_message(b"Price too old: ", &hop),
+                ));
+            }
+            // Only compares the round ids. The price-aggregator has no `answered_in_round`,
+            // so a feed stuck on its last round is not detected here, but only by the age of
+            // its timestamp, and only when a maximum price age is set.
+            if hop.round_id < self.last_round_id(&hop.from, &hop.to).get() {
+                return Result::Err(Refund::new(
+                    RefundReason::RoundRegression,
+                    self.hop_message(b"Price from an earlier round than already used: ", &hop),
+                ));
+            }
+        }
+
+        let max_rate_change_bps = self.max_rate_change_bps().get();
+        let reference_rate = self.reference_rate(source_token, target_token);
+        let max_reference_age = self.max_reference_age().get();
+        let reference_expired = max_reference_age > 0
+            && current_timestamp
+                .saturating_sub(self.reference_timestamp(source_token, target_token).get())
+                > max_reference_age;
+        if max_rate_change_bps > 0
+            && !reference_rate.is_empty()
+            && !reference_expired
+            && rate.change_exceeds(&reference_rate.get(), max_rate_change_bps)
+        {
+            return Result::Err(Refund::new(
+                RefundReason::RateChangeTooLarge,
+                "Rate moved too much since the last swap".as_bytes().into(),
+            ));
+        }
+
+        Result::Ok(())
+    }
+
+    fn record_swap_rate(
+        &self,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+        rate: &Rate<Self::Api>,
+        route: &ManagedVec<RouteHop<Self::Api>>,
+    ) {
+        for hop in route.iter() {
+            self.last_round_id(&hop.from, &hop.to).set(hop.round_id);
+        }
+        self.reference_rate(source_token, target_token).set(rate);
+        self.reference_timestamp(source_token, target_token)
+            .set(self.blockchain().get_block_timestamp());
+    }
+
+    fn hop_message(&self, prefix: &[u8], hop: &RouteHop<Self::Api>) -> ManagedBuffer {
+        let mut message = ManagedBuffer::new_from_bytes(prefix);
+        message.append(&hop.from);
+        message.append_bytes(b"/");
+        message.append(&hop.to);
+        message
+    }
+
+    #[view(getMaxPriceAge)]
+    #[storage_mapper("max_price_age")]
+    fn max_price_age(&self) -> SingleValueMapper<u64>;
+
+    #[view(getMaxRateChange)]
+    #[storage_mapper("max_rate_change_bps")]
+    fn max_rate_change_bps(&self) -> SingleValueMapper<u32>;
+
+    #[view(getMaxReferenceAge)]
+    #[storage_mapper("max_reference_age")]
+    fn max_reference_age(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("last_round_id")]
+    fn last_round_id(
+        &self,
+        from_ticker: &ManagedBuffer,
+        to_ticker: &ManagedBuffer,
+    ) -> SingleValueMapper<u32>;
+
+    #[storage_mapper("reference_rate")]
+    fn reference_rate(
+        &self,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<Rate<Self::Api>>;
+
+    #[storage_mapper("reference_timestamp")]
+    fn reference_timestamp(
+        &self,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<u64>;
+}
@@ -3,81 +3,183 @@
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
-extern crate aggregator;
-use aggregator::aggregator_interface::DescriptionVec;
-
-use crate::aggregator::aggregator_interface::Round;
-
-const MAX_FORMATTED_NUMBER_CHARS: usize = 30;
-const MAX_PADDING_LEN: usize = 18;
-const MAX_PADDED_NUMBER_CHARS: usize = MAX_FORMATTED_NUMBER_CHARS + MAX_PADDING_LEN;
+use elrond_wasm::contract_base::ErrorHelper;
+use fixed_point_format::{write_unsigned, Format, Sink, MAX_MAGNITUDE_LEN};
+
+pub mod circuit_breaker;
+pub mod limits;
+pub mod liquidity;
+pub mod outcome;
+pub mod quote;
+pub mod rate;
+use outcome::{Refund, RefundEvent, RefundReason, SwapEvent};
+use quote::{Quote, RouteHop};
+use rate::Rate;
+
+const MAX_FEE_BPS: u32 = 10_000;
+const MAX_ROUTE_INTERMEDIATES: usize = 3;
+const MAX_TOKEN_DECIMALS: u8 = 18;
+const MAX_BASKET_TARGETS: usize = 5;
+const TOTAL_BASKET_WEIGHT: u32 = 10_000;
+
+/// Appends the formatted numbers to a managed buffer
+struct ManagedBufferSink<'a, M: ManagedTypeApi>(&'a mut ManagedBuffer<M>);
+
+impl<'a, M: ManagedTypeApi> Sink for ManagedBufferSink<'a, M> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.append_bytes(bytes);
+    }
+}
 
-pub fn format_biguint<M: ManagedTypeApi>(
-    mut number: BigUint<M>,
-) -> ArrayVec<u8, MAX_FORMATTED_NUMBER_CHARS> {
-    let radix = BigUint::from(10u32);
-    let mut result = ArrayVec::<u8, MAX_FORMATTED_NUMBER_CHARS>::new();
+/// Appends the decimal digits of `number` to `buffer`
+pub fn append_biguint<M: ManagedTypeApi>(buffer: &mut ManagedBuffer<M>, number: &BigUint<M>) {
+    append_formatted(buffer, number, &Format::integer());
+}
 
-    loop {
-        let last_digit = &number % &radix;
-        number /= &radix;
+/// Appends `number` to `buffer` as a fixed-point number with `decimals` decimals
+pub fn append_fixed_precision<M: ManagedTypeApi>(
+    buffer: &mut ManagedBuffer<M>,
+    number: &BigUint<M>,
+    decimals: usize,
+) {
+    append_formatted(buffer, number, &Format::fixed(decimals));
+}
 
-        let digit = last_digit.to_u64().unwrap_or_default() as u8;
-        result.push('0' as u8 + digit);
+fn append_formatted<M: ManagedTypeApi>(
+    buffer: &mut ManagedBuffer<M>,
+    number: &BigUint<M>,
+    format: &Format,
+) {
+    let magnitude = number.to_bytes_be_buffer();
+    if magnitude.len() > MAX_MAGNITUDE_LEN {
+        ErrorHelper::<M>::signal_error_with_message("Number too large to format");
+    }
+    let mut bytes = [0u8; MAX_MAGNITUDE_LEN];
+    let magnitude = magnitude.load_to_byte_array(&mut bytes);
+    // the length was checked above
+    let _ = write_unsigned(magnitude, format, &mut ManagedBufferSink(buffer));
+}
 
-        if number == 0u32 {
-            break;
-        }
+#[elrond_wasm::contract]
+pub trait EgldEsdtExchange:
+    price_aggregator_proxy::PriceAggregatorModule
+    + liquidity::LiquidityModule
+    + limits::LimitsModule
+    + circuit_breaker::CircuitBreakerModule
+{
+    #[init]
+    fn init(&self, price_aggregator_address: ManagedAddress) {
+        self.price_aggregator_address()
+            .set(&price_aggregator_address);
     }
 
-    result.into_iter().rev().collect()
-}
+    /// Sets the fee, in basis points of the output, kept by the exchange when
+    /// converting `source_token` into `target_token`
+    #[only_owner]
+    #[endpoint(setPairFee)]
+    fn set_pair_fee(
+        &self,
+        source_token: EgldOrEsdtTokenIdentifier,
+        target_token: EgldOrEsdtTokenIdentifier,
+        fee_bps: u32,
+    ) {
+        require!(fee_bps < MAX_FEE_BPS, "Fee must be less than 100%");
+        self.pair_fee(&source_token, &target_token).set(fee_bps);
+    }
 
-pub fn format_fixed_precision<M: ManagedTypeApi>(
-    number: BigUint<M>,
-    decimals: usize,
-) -> ArrayVec<u8, MAX_FORMATTED_NUMBER_CHARS> {
-    let formatted_number = format_biguint(number);
-    let padding_length = (decimals + 1)
-        .checked_sub(formatted_number.len())
-        .unwrap_or_default();
-
-    // is there a better way to do this?
-    let mut padding = ArrayVec::<u8, MAX_PADDING_LEN>::new();
-    for _ in 0..padding_length {
-        padding.push(b'0');
+    /// Sets the spread, in basis points of the rate, by which the price feeds' rate
+    /// is lowered when converting `source_token` into `target_token`. Unlike the fee,
+    /// it is part of the quoted rate; both stay in the pool.
+    #[only_owner]
+    #[endpoint(setPairSpread)]
+    fn set_pair_spread(
+        &self,
+        source_token: EgldOrEsdtTokenIdentifier,
+        target_token: EgldOrEsdtTokenIdentifier,
+        spread_bps: u32,
+    ) {
+        require!(spread_bps < MAX_FEE_BPS, "Spread must be less than 100%");
+        self.pair_spread(&source_token, &target_token)
+            .set(spread_bps);
     }
 
-    let mut padded_number = ArrayVec::<u8, MAX_PADDED_NUMBER_CHARS>::new();
-    padded_number.extend(padding);
-    padded_number.extend(formatted_number);
+    /// Sets the ticker under which the price-aggregator publishes the prices of a token,
+    /// and the number of decimals of the token's amounts
+    #[only_owner]
+    #[endpoint(setTokenTicker)]
+    fn set_token_ticker(
+        &self,
+        token: EgldOrEsdtTokenIdentifier,
+        ticker: ManagedBuffer,
+        decimals: u8,
+    ) {
+        require!(decimals <= MAX_TOKEN_DECIMALS, "Too many decimals");
+        self.token_decimals().insert(token.clone(), decimals);
+        self.token_tickers().insert(token, ticker);
+    }
 
-    let digits_before_dot = padded_number.len() - decimals;
+    #[only_owner]
+    #[endpoint(removeTokenTicker)]
+    fn remove_token_ticker(&self, token: EgldOrEsdtTokenIdentifier) {
+        require!(
+            self.token_tickers().remove(&token).is_some(),
+            "Token ticker not found"
+        );
+        self.token_decimals().remove(&token);
+    }
 
-    let left = padded_number.as_slice().iter().take(digits_before_dot);
-    let dot = core::iter::once(&('.' as u8));
-    let right = padded_number.as_slice().iter().skip(digits_before_dot);
-    left.chain(dot).chain(right).cloned().collect()
-}
+    /// Routes the swaps from `source_token` to `target_token` through the price feeds
+    /// of the given intermediate tickers, e.g. `USD` for a MEX to EGLD swap using the
+    /// MEX/USD and USD/EGLD feeds
+    #[only_owner]
+    #[endpoint(setRoute)]
+    fn set_route(
+        &self,
+        source_token: EgldOrEsdtTokenIdentifier,
+        target_token: EgldOrEsdtTokenIdentifier,
+        intermediate_tickers: MultiValueEncoded<ManagedBuffer>,
+    ) {
+        require!(
+            !intermediate_tickers.is_empty(),
+            "Route needs at least one intermediate ticker"
+        );
+        require!(
+            intermediate_tickers.len() <= MAX_ROUTE_INTERMEDIATES,
+            "Too many intermediate tickers"
+        );
 
-#[elrond_wasm::contract]
-pub trait EgldEsdtExchange {
-    #[init]
-    fn init(&self, aggregator: ManagedAddress) {
-        self.aggregator().set(&aggregator);
+        let mut route = self.routes(&source_token, &target_token);
+        route.clear();
+        for ticker in intermediate_tickers {
+            route.push(&ticker);
+        }
     }
 
+    /// Makes the swaps from `source_token` to `target_token` use the tokens' own feed again
     #[only_owner]
-    #[payable("*")]
-    #[endpoint(deposit)]
-    fn deposit(&self) {
-        let (payment_token, payment) = self.call_value().egld_or_single_fungible_esdt();
-        self.increase_balance(&payment_token, &payment);
+    #[endpoint(removeRoute)]
+    fn remove_route(
+        &self,
+        source_token: EgldOrEsdtTokenIdentifier,
+        target_token: EgldOrEsdtTokenIdentifier,
+    ) {
+        let mut route = self.routes(&source_token, &target_token);
+        require!(!route.is_empty(), "Route not found");
+        route.clear();
     }
 
+    /// Converts the payment into the target token, at the rate of the price feed
+    /// of the two tokens' tickers, or of its inverse, or at the rate composed from
+    /// the feeds of the pair's route. If the rate is not available,
+    /// the output is below `min_amount_out` or the exchange cannot pay out,
+    /// the payment is refunded.
     #[payable("*")]
     #[endpoint(exchange)]
-    fn exchange(&self, target_token: EgldOrEsdtTokenIdentifier) {
+    fn exchange(
+        &self,
+        target_token: EgldOrEsdtTokenIdentifier,
+        min_amount_out: OptionalValue<BigUint>,
+    ) {
         let (source_token, payment) = self.call_value().egld_or_single_fungible_esdt();
         require!(payment > 0, "Payment must be more than 0");
         require!(
@@ -88,203 +190,406 @@ pub trait EgldEsdtExchange {
             self.balance().contains_key(&target_token),
             "Target ESDT token not supported by the exchange"
         );
-        self.increase_balance(&source_token, &payment);
-
-        self.aggregator_interface_proxy(self.aggregator().get())
-            .latest_round_data()
-            .async_call()
-            .with_callback(self.callbacks().finalize_exchange(
-                self.blockchain().get_caller(),
-                payment,
-                source_token,
-                target_token,
-            ))
-            .call_and_exit();
+
+        let min_amount_out = min_amount_out
+            .into_option()
+            .unwrap_or_else(|| BigUint::zero());
+        let caller = self.blockchain().get_caller();
+        match self.swap(
+            &caller,
+            &payment,
+            &source_token,
+            &target_token,
+            &min_amount_out,
+        ) {
+            Some(amount_out) => self.send().direct(&caller, &target_token, 0, &amount_out),
+            None => self.send().direct(&caller, &source_token, 0, &payment),
+        }
     }
 
-    fn check_aggregator_tokens(
+    /// Converts each token of a multi-ESDT payment into a basket of target tokens,
+    /// splitting each payment by the targets' weights, in basis points, which must
+    /// add up to 10000. With a single target, of weight 10000, every payment is
+    /// converted into that token. The converted amounts are sent back in a single
+    /// transfer, and the parts which could not be converted are refunded in another.
+    #[payable("*")]
+    #[endpoint(exchangeMulti)]
+    fn exchange_multi(
         &self,
-        description: DescriptionVec,
-        source_token: &EgldOrEsdtTokenIdentifier,
-        target_token: &EgldOrEsdtTokenIdentifier,
-    ) -> Result<bool, ManagedBuffer> {
-        let delimiter_position = description
-            .as_slice()
-            .iter()
-            .position(|item| *item == '/' as u8)
-            .ok_or(ManagedBuffer::from(
-                "Invalid aggregator description format (expected 2 tokens)".as_bytes(),
-            ))?;
-        let (first, second) = description.split_at(delimiter_position);
-        let first_token = EgldOrEsdtTokenIdentifier::parse(ManagedBuffer::new_from_bytes(first));
-        let second_token = EgldOrEsdtTokenIdentifier::parse(ManagedBuffer::new_from_bytes(second));
-        if &first_token == source_token && &second_token == target_token {
-            return Result::Ok(false);
+        targets: MultiValueEncoded<MultiValue2<EgldOrEsdtTokenIdentifier, u32>>,
+    ) {
+        let payments = self.call_value().all_esdt_transfers();
+        require!(!payments.is_empty(), "No payment");
+
+        let mut basket = ArrayVec::<(EgldOrEsdtTokenIdentifier, u32), MAX_BASKET_TARGETS>::new();
+        let mut total_weight = 0u32;
+        for target in targets {
+            let (target_token, weight_bps) = target.into_tuple();
+            require!(
+                self.balance().contains_key(&target_token),
+                "Target ESDT token not supported by the exchange"
+            );
+            require!(
+                weight_bps > 0 && weight_bps <= TOTAL_BASKET_WEIGHT,
+                "Target weights must be between 1 and 10000"
+            );
+            require!(basket.len() < MAX_BASKET_TARGETS, "Too many target tokens");
+            total_weight += weight_bps;
+            basket.push((target_token, weight_bps));
         }
-        if &first_token == target_token && &second_token == source_token {
-            return Result::Ok(true);
+        require!(
+            total_weight == TOTAL_BASKET_WEIGHT,
+            "Target weights must add up to 10000"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let mut payouts = ArrayVec::<BigUint, MAX_BASKET_TARGETS>::new();
+        for _ in 0..basket.len() {
+            payouts.push(BigUint::zero());
         }
-        Result::Err(
-            "Exchange between chosen token types not supported."
-                .as_bytes()
-                .into(),
-        )
-    }
+        let mut refunds = ManagedVec::<Self::Api, EsdtTokenPayment<Self::Api>>::new();
+        for payment in self.merge_payments(&payments).into_iter() {
+            let source_token = EgldOrEsdtTokenIdentifier::esdt(payment.token_identifier.clone());
+            require!(
+                self.balance().contains_key(&source_token),
+                "ESDT provided as payment not supported by the exchange"
+            );
+            // the limits apply to the whole amount of the token, rather than to each leg
+            let limits_check = self.check_swap_limits(&caller, &source_token, &payment.amount);
+            let mut refund = BigUint::zero();
+            let mut remaining = payment.amount.clone();
+            for (index, (target_token, weight_bps)) in basket.iter().enumerate() {
+                let amount = if index + 1 == basket.len() {
+                    remaining.clone()
+                } else {
+                    &payment.amount * &BigUint::from(*weight_bps)
+                        / &BigUint::from(TOTAL_BASKET_WEIGHT)
+                };
+                remaining -= &amount;
+                if amount == 0 {
+                    continue;
+                }
 
-    fn convert(
-        &self,
-        amount: &BigUint,
-        source_token: &EgldOrEsdtTokenIdentifier,
-        target_token: &EgldOrEsdtTokenIdentifier,
-        multiplier: &BigUint,
-        divisor: &BigUint,
-        precision_factor: &BigUint,
-        decimals: usize,
-    ) -> Result<(BigUint, ManagedBuffer), ManagedBuffer> {
-        if divisor == &BigUint::zero() {
-            return Result::Err("Convert - dividing by 0".as_bytes().into());
+                let amount_out = match &limits_check {
+                    Result::Ok(()) => self.swap(
+                        &caller,
+                        &amount,
+                        &source_token,
+                        target_token,
+                        &BigUint::zero(),
+                    ),
+                    Result::Err(limit_refund) => {
+                        self.refund_event(
+                            &caller,
+                            &source_token,
+                            target_token,
+                            &RefundEvent {
+                                amount: amount.clone(),
+                                reason: limit_refund.reason,
+                                message: limit_refund.message.clone(),
+                            },
+                        );
+                        None
+                    }
+                };
+                match amount_out {
+                    Some(amount_out) => payouts[index] += amount_out,
+                    None => refund += amount,
+                }
+            }
+
+            if refund > 0 {
+                refunds.push(EsdtTokenPayment::new(payment.token_identifier, 0, refund));
+            }
+        }
+
+        let mut esdt_payouts = ManagedVec::<Self::Api, EsdtTokenPayment<Self::Api>>::new();
+        for ((target_token, _), amount_out) in basket.into_iter().zip(payouts.into_iter()) {
+            if amount_out == 0 {
+                continue;
+            }
+            if target_token.is_egld() {
+                self.send().direct_egld(&caller, &amount_out);
+            } else {
+                esdt_payouts.push(EsdtTokenPayment::new(
+                    target_token.unwrap_esdt(),
+                    0,
+                    amount_out,
+                ));
+            }
+        }
+        if !esdt_payouts.is_empty() {
+            self.send().direct_multi(&caller, &esdt_payouts);
+        }
+        if !refunds.is_empty() {
+            self.send().direct_multi(&caller, &refunds);
         }
-        let converted_amount = amount * multiplier / divisor.clone();
-        let rate = multiplier * precision_factor / divisor.clone();
-        let message = self.conversion_message(
-            amount,
-            source_token,
-            &rate,
-            decimals,
-            &converted_amount,
-            target_token,
-        )?;
-        Result::Ok((converted_amount, message))
     }
 
-    fn get_converted_sum(
+    /// Adds up the payments of each token, so that a token paid in several transfers
+    /// is converted, and checked against the swap limits, as a single payment
+    fn merge_payments(
         &self,
-        payment: &BigUint,
-        source_token: &EgldOrEsdtTokenIdentifier,
-        target_token: &EgldOrEsdtTokenIdentifier,
-        exchange_rate: &BigUint,
-        decimals: usize,
-        reverse_exchange: bool,
-    ) -> Result<(BigUint, ManagedBuffer), ManagedBuffer> {
-        let precision_factor = BigUint::from(10u64.pow(decimals as u32));
-        if !reverse_exchange {
-            self.convert(
-                payment,
-                source_token,
-                target_token,
-                exchange_rate,
-                &precision_factor,
-                &precision_factor,
-                decimals,
-            )
-        } else {
-            self.convert(
-                payment,
-                source_token,
-                target_token,
-                &precision_factor,
-                exchange_rate,
-                &precision_factor,
-                decimals,
-            )
+        payments: &ManagedVec<EsdtTokenPayment<Self::Api>>,
+    ) -> ManagedVec<EsdtTokenPayment<Self::Api>> {
+        let mut merged = ManagedVec::<Self::Api, EsdtTokenPayment<Self::Api>>::new();
+        for payment in payments.iter() {
+            require!(
+                payment.token_nonce == 0,
+                "Only fungible ESDT payments are accepted"
+            );
+            let existing = merged.iter().position(|merged_payment| {
+                merged_payment.token_identifier == payment.token_identifier
+            });
+            match existing {
+                Some(index) => {
+                    let mut merged_payment = merged.get(index);
+                    merged_payment.amount += &payment.amount;
+                    let _ = merged.set(index, &merged_payment);
+                }
+                None => merged.push(payment),
+            }
         }
+        merged
     }
 
-    fn try_convert(
+    /// Converts `payment` of `source_token` into `target_token`, emitting the outcome
+    /// as an event. Returns the amount to pay out, or `None` if the payment has to
+    /// be refunded.
+    fn swap(
         &self,
-        result: ManagedAsyncCallResult<OptionalValue<Round<Self::Api>>>,
+        caller: &ManagedAddress,
         payment: &BigUint,
         source_token: &EgldOrEsdtTokenIdentifier,
         target_token: &EgldOrEsdtTokenIdentifier,
-    ) -> Result<(BigUint, ManagedBuffer), ManagedBuffer> {
-        match result {
-            ManagedAsyncCallResult::Ok(optional_result_round) => {
-                let option_round = match optional_result_round {
-                    OptionalValue::Some(round) => Some(round),
-                    OptionalValue::None => None,
-                };
-                let error_message: ManagedBuffer = b"no round data"[..].into();
-                let round = option_round.ok_or(error_message)?;
-                let error_message: ManagedBuffer = b"no aggregator data"[..].into();
-                let submission = round.answer.ok_or(error_message)?;
-                if submission.values.len() != 1 {
-                    let error_message: ManagedBuffer = b"invalid aggregator data format"[..].into();
-                    return Result::Err(error_message);
-                }
-                let exchange_rate = &submission.values[0];
-                let reverse_exchange =
-                    self.check_aggregator_tokens(round.description, source_token, target_token)?;
-                let (converted_amount, conversion_message) = self.get_converted_sum(
-                    payment,
+        min_amount_out: &BigUint,
+    ) -> Option<BigUint> {
+        match self.try_convert(caller, payment, source_token, target_token, min_amount_out) {
+            Result::Ok(quote) => {
+                self.increase_balance(source_token, payment);
+
+                let message = self.conversion_message(payment, source_token, &quote, target_token);
+                let amount_out = quote.amount_out.clone();
+                self.swap_event(
+                    caller,
                     source_token,
                     target_token,
-                    exchange_rate,
-                    round.decimals as usize,
-                    reverse_exchange,
-                )?;
-                match self.checked_decrease_balance(target_token, &converted_amount) {
-                    Result::Err(mut error) => {
-                        error.append_bytes(b" (");
-                        error.append(&conversion_message);
-                        error.append_bytes(b")");
-
-                        Result::Err(error)
-                    }
-                    Result::Ok(()) => Result::Ok((converted_amount, conversion_message)),
-                }
+                    &SwapEvent {
+                        amount_in: payment.clone(),
+                        amount_out: quote.amount_out,
+                        fee: quote.fee,
+                        rate: quote.rate,
+                        rate_decimals: quote.rate_decimals,
+                        route: quote.route,
+                        message,
+                    },
+                );
+                Some(amount_out)
             }
-            ManagedAsyncCallResult::Err(error) => {
-                self.checked_decrease_balance(source_token, &payment)?;
-                let mut error_msg = ManagedBuffer::new_from_bytes(
-                    b"Error when getting the price feed from the aggregator: ",
+            Result::Err(refund) => {
+                self.refund_event(
+                    caller,
+                    source_token,
+                    target_token,
+                    &RefundEvent {
+                        amount: payment.clone(),
+                        reason: refund.reason,
+                        message: refund.message,
+                    },
                 );
-                error_msg.append(&error.err_msg);
-
-                Result::Err(error_msg)
+                None
             }
         }
     }
 
-    #[callback]
-    fn finalize_exchange(
+    /// Returns the amount that exchanging `amount` of `source_token` would pay out
+    /// at the current rate, failing if the exchange cannot pay it out
+    #[view(quote)]
+    fn quote(
         &self,
-        #[call_result] result: ManagedAsyncCallResult<OptionalValue<Round<Self::Api>>>,
-        caller: ManagedAddress,
-        payment: BigUint,
         source_token: EgldOrEsdtTokenIdentifier,
+        amount: BigUint,
         target_token: EgldOrEsdtTokenIdentifier,
-    ) {
-        match self.try_convert(result, &payment, &source_token, &target_token) {
-            Result::Ok((converted_payment, conversion_message)) => {
-                let mut message = ManagedBuffer::new_from_bytes(b"exchange succesful (");
-                message.append(&conversion_message);
-                message.append_bytes(b")");
-
-                //TODO - save message in event
-                self.send()
-                    .direct(&caller, &target_token, 0, &converted_payment);
-            }
-            Result::Err(error) => {
-                let mut message = ManagedBuffer::new_from_bytes(b"refund (");
-                message.append(&error);
-                message.append_bytes(b")");
+    ) -> Quote<Self::Api> {
+        let (quote, rate) = self
+            .get_quote(&amount, &source_token, &target_token)
+            .unwrap_or_else(|refund| sc_panic!(refund.message));
+        self.check_price_safety(&source_token, &target_token, &rate, &quote.route)
+            .unwrap_or_else(|refund| sc_panic!(refund.message));
+        self.check_balance(&target_token, &quote.amount_out)
+            .unwrap_or_else(|error| sc_panic!(error));
+        let target_balance = self
+            .balance()
+            .get(&target_token)
+            .unwrap_or_else(|| BigUint::zero());
+        self.check_reserve_floor(&target_token, &target_balance, &quote.amount_out)
+            .unwrap_or_else(|refund| sc_panic!(refund.message));
+        quote
+    }
 
-                //TODO - save message in event
-                self.send().direct(&caller, &source_token, 0, &payment);
-            }
+    fn get_quote(
+        &self,
+        amount: &BigUint,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+    ) -> Result<(Quote<Self::Api>, Rate<Self::Api>), Refund<Self::Api>> {
+        let (rate, route) = self
+            .get_rate(source_token, target_token)
+            .map_err(|error| Refund::new(RefundReason::PriceUnavailable, error))?;
+        if !rate.is_valid() {
+            return Result::Err(Refund::new(
+                RefundReason::InvalidRate,
+                "Invalid exchange rate".as_bytes().into(),
+            ));
+        }
+
+        let source_decimals = self.token_decimals().get(source_token).unwrap_or_default();
+        let target_decimals = self.token_decimals().get(target_token).unwrap_or_default();
+        let spread_bps = self.pair_spread(source_token, target_token).get();
+        let quoted_rate = rate.clone().less_bps(spread_bps);
+        let converted_amount = quoted_rate
+            .clone()
+            .between_units(source_decimals, target_decimals)
+            .convert(amount);
+        let fee_bps = self.pair_fee(source_token, target_token).get();
+        let fee = &converted_amount * &BigUint::from(fee_bps) / &BigUint::from(MAX_FEE_BPS);
+
+        let rate_decimals = self.route_decimals(&route);
+
+        let quote = Quote {
+            amount_out: converted_amount - &fee,
+            fee,
+            rate: quoted_rate.to_fixed_point(rate_decimals),
+            rate_decimals,
+            route,
+        };
+        Result::Ok((quote, rate))
+    }
+
+    /// Reads the rate between the tokens from the price-aggregator, which has to be
+    /// in the same shard, composing the feeds along the pair's route.
+    /// Returns the rate together with the feeds it was read from.
+    fn get_rate(
+        &self,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+    ) -> Result<(Rate<Self::Api>, ManagedVec<RouteHop<Self::Api>>), ManagedBuffer> {
+        let mut from_ticker = self.get_token_ticker(source_token)?;
+        let target_ticker = self.get_token_ticker(target_token)?;
+
+        let mut rate = Rate::identity();
+        let mut route = ManagedVec::new();
+        let routes = self.routes(source_token, target_token);
+        let tickers = routes.iter().chain(core::iter::once(target_ticker));
+        for to_ticker in tickers {
+            let (hop_rate, hop) = self.get_hop_rate(&from_ticker, &to_ticker)?;
+            rate = rate.then(hop_rate);
+            route.push(hop);
+            from_ticker = to_ticker;
+        }
+
+        Result::Ok((rate, route))
+    }
+
+    /// The rate of the `from/to` feed, or the inverse of the `to/from` feed
+    fn get_hop_rate(
+        &self,
+        from_ticker: &ManagedBuffer,
+        to_ticker: &ManagedBuffer,
+    ) -> Result<(Rate<Self::Api>, RouteHop<Self::Api>), ManagedBuffer> {
+        let (feed, inverted) = match self
+            .get_full_result_for_pair(from_ticker.clone(), to_ticker.clone())
+        {
+            Some(feed) => (feed, false),
+            None => match self.get_full_result_for_pair(to_ticker.clone(), from_ticker.clone()) {
+                Some(feed) => (feed, true),
+                None => {
+                    let mut err_msg = ManagedBuffer::new_from_bytes(b"No price feed between ");
+                    err_msg.append(from_ticker);
+                    err_msg.append_bytes(b" and ");
+                    err_msg.append(to_ticker);
+                    return Result::Err(err_msg);
+                }
+            },
+        };
+
+        let rate = Rate::from_price(feed.price, feed.decimals);
+        let hop = RouteHop {
+            from: from_ticker.clone(),
+            to: to_ticker.clone(),
+            round_id: feed.round_id,
+            timestamp: feed.timestamp,
+            decimals: feed.decimals,
+            inverted,
+        };
+        if inverted {
+            Result::Ok((rate.inverse(), hop))
+        } else {
+            Result::Ok((rate, hop))
         }
     }
 
-    fn increase_balance(&self, token_identifier: &EgldOrEsdtTokenIdentifier, amount: &BigUint) {
-        let mut balance = self
+    /// The rate of a route is shown with the largest number of decimals of its feeds
+    fn route_decimals(&self, route: &ManagedVec<RouteHop<Self::Api>>) -> u8 {
+        route
+            .iter()
+            .map(|hop| hop.decimals)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn get_token_ticker(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> Result<ManagedBuffer, ManagedBuffer> {
+        self.token_tickers().get(token).ok_or_else(|| {
+            let mut err_msg = ManagedBuffer::new_from_bytes(b"No ticker set for ");
+            err_msg.append(&token.clone().into_name());
+            err_msg
+        })
+    }
+
+    fn try_convert(
+        &self,
+        caller: &ManagedAddress,
+        payment: &BigUint,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+        min_amount_out: &BigUint,
+    ) -> Result<Quote<Self::Api>, Refund<Self::Api>> {
+        let (quote, rate) = self.get_quote(payment, source_token, target_token)?;
+        self.check_price_safety(source_token, target_token, &rate, &quote.route)?;
+        if &quote.amount_out < min_amount_out {
+            let mut error = ManagedBuffer::new_from_bytes(b"Output below minimum of ");
+            append_biguint(&mut error, min_amount_out);
+            error.append_bytes(b" (");
+            error.append(&self.conversion_message(payment, source_token, &quote, target_token));
+            error.append_bytes(b")");
+
+            return Result::Err(Refund::new(RefundReason::BelowMinimumOutput, error));
+        }
+
+        self.check_swap_limits(caller, source_token, payment)?;
+        if let Result::Err(mut error) = self.check_balance(target_token, &quote.amount_out) {
+            error.append_bytes(b" (");
+            error.append(&self.conversion_message(payment, source_token, &quote, target_token));
+            error.append_bytes(b")");
+
+            return Result::Err(Refund::new(RefundReason::InsufficientLiquidity, error));
+        }
+        let target_balance = self
             .balance()
-            .get(&token_identifier)
+            .get(target_token)
             .unwrap_or_else(|| BigUint::zero());
-        balance += amount;
-        self.balance().insert(token_identifier.clone(), balance);
+        self.check_reserve_floor(target_token, &target_balance, &quote.amount_out)?;
+
+        self.decrease_balance(target_token, &quote.amount_out);
+        self.record_swap_volume(caller, source_token, payment);
+        self.record_swap_rate(source_token, target_token, &rate, &quote.route);
+        Result::Ok(quote)
     }
 
-    fn checked_decrease_balance(
+    fn check_balance(
         &self,
         token_identifier: &EgldOrEsdtTokenIdentifier,
         amount: &BigUint,
@@ -293,14 +598,13 @@ pub trait EgldEsdtExchange {
             Some(balance) => {
                 if &balance < amount {
                     let mut err_msg = ManagedBuffer::new_from_bytes(b"Insufficient balance: only ");
-                    err_msg.append_bytes(format_biguint(balance).as_slice());
+                    append_biguint(&mut err_msg, &balance);
                     err_msg.append_bytes(b" of ");
                     err_msg.append(&token_identifier.clone().into_name());
                     err_msg.append_bytes(b" available");
 
                     Result::Err(err_msg)
                 } else {
-                    self.decrease_balance(token_identifier, amount);
                     Result::Ok(())
                 }
             }
@@ -314,44 +618,87 @@ pub trait EgldEsdtExchange {
         }
     }
 
-    fn decrease_balance(&self, token_identifier: &EgldOrEsdtTokenIdentifier, amount: &BigUint) {
-        let mut balance = self
-            .balance()
-            .get(&token_identifier)
-            .unwrap_or_else(|| BigUint::zero());
-        balance -= amount;
-        self.balance().insert(token_identifier.clone(), balance);
-    }
-
     fn conversion_message(
         &self,
         payment: &BigUint,
         source_token: &EgldOrEsdtTokenIdentifier,
-        rate: &BigUint,
-        rate_precision: usize,
-        converted_token: &BigUint,
+        quote: &Quote<Self::Api>,
         target_token: &EgldOrEsdtTokenIdentifier,
-    ) -> Result<ManagedBuffer, ManagedBuffer> {
+    ) -> ManagedBuffer {
         let mut message = ManagedBuffer::new_from_bytes(b"conversion from ");
-        message.append_bytes(format_biguint(payment.clone()).as_slice());
+        append_biguint(&mut message, payment);
         message.append_bytes(b" of ");
         message.append(&source_token.clone().into_name());
         message.append_bytes(b", using exchange rate ");
-        message.append_bytes(format_fixed_precision(rate.clone(), rate_precision).as_slice());
+        append_fixed_precision(&mut message, &quote.rate, quote.rate_decimals as usize);
         message.append_bytes(b", results in ");
-        message.append_bytes(format_biguint(converted_token.clone()).as_slice());
+        append_biguint(&mut message, &quote.amount_out);
         message.append_bytes(b" of ");
         message.append(&target_token.clone().into_name());
+        if quote.route.len() > 1 {
+            message.append_bytes(b", routed through ");
+            for (index, hop) in quote.route.iter().skip(1).enumerate() {
+                if index > 0 {
+                    message.append_bytes(b", ");
+                }
+                message.append(&hop.from);
+            }
+        }
+        if quote.fee > 0u32 {
+            message.append_bytes(b", after a fee of ");
+            append_biguint(&mut message, &quote.fee);
+        }
 
-        Result::Ok(message)
+        message
     }
 
-    #[storage_mapper("aggregator")]
-    fn aggregator(&self) -> SingleValueMapper<ManagedAddress>;
+    #[event("swap")]
+    fn swap_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] source_token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] target_token: &EgldOrEsdtTokenIdentifier,
+        swap: &SwapEvent<Self::Api>,
+    );
+
+    #[event("refund")]
+    fn refund_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] source_token: &EgldOrEsdtTokenIdentifier,
+        #[indexed] target_token: &EgldOrEsdtTokenIdentifier,
+        refund: &RefundEvent<Self::Api>,
+    );
+
+    #[view(getTokenTickers)]
+    #[storage_mapper("token_tickers")]
+    fn token_tickers(&self) -> MapMapper<EgldOrEsdtTokenIdentifier, ManagedBuffer>;
+
+    #[view(getTokenDecimals)]
+    #[storage_mapper("token_decimals")]
+    fn token_decimals(&self) -> MapMapper<EgldOrEsdtTokenIdentifier, u8>;
+
+    #[view(getRoute)]
+    #[storage_mapper("routes")]
+    fn routes(
+        &self,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+    ) -> VecMapper<ManagedBuffer>;
 
-    #[storage_mapper("balance")]
-    fn balance(&self) -> MapMapper<EgldOrEsdtTokenIdentifier, BigUint>;
+    #[view(getPairFee)]
+    #[storage_mapper("pair_fee")]
+    fn pair_fee(
+        &self,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<u32>;
 
-    #[proxy]
-    fn aggregator_interface_proxy(&self, to: ManagedAddress) -> aggregator::Proxy<Self::Api>;
+    #[view(getPairSpread)]
+    #[storage_mapper("pair_spread")]
+    fn pair_spread(
+        &self,
+        source_token: &EgldOrEsdtTokenIdentifier,
+        target_token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<u32>;
 }
@@ -0,0 +1,209 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::outcome::{Refund, RefundReason};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Limits on the amounts of a token paid into swaps; zero means no limit
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct SwapLimits<M: ManagedTypeApi> {
+    pub max_per_swap: BigUint<M>,
+    pub user_daily_cap: BigUint<M>,
+    pub global_daily_cap: BigUint<M>,
+}
+
+/// The amount swapped during `day`, counted in days since the epoch
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct DailyVolume<M: ManagedTypeApi> {
+    pub day: u64,
+    pub amount: BigUint<M>,
+}
+
+/// How much more of a token can be swapped today; `None` if there is no cap
+#[derive(TopEncode, TopDecode, TypeAbi)]
+pub struct DailyCapacity<M: ManagedTypeApi> {
+    pub user_remaining: Option<BigUint<M>>,
+    pub global_remaining: Option<BigUint<M>>,
+}
+
+/// Caps the amounts swapped per swap and per day, for each user and overall,
+/// and keeps a reserve of each token which swaps cannot pay out.
+/// Volumes are counted in the token paid into the swap.
+#[elrond_wasm::module]
+pub trait LimitsModule {
+    #[only_owner]
+    #[endpoint(setSwapLimits)]
+    fn set_swap_limits(
+        &self,
+        token: EgldOrEsdtTokenIdentifier,
+        max_per_swap: BigUint,
+        user_daily_cap: BigUint,
+        global_daily_cap: BigUint,
+    ) {
+        self.swap_limits(&token).set(&SwapLimits {
+            max_per_swap,
+            user_daily_cap,
+            global_daily_cap,
+        });
+    }
+
+    /// Swaps which would leave less than `floor` of `token` in the exchange are refused
+    #[only_owner]
+    #[endpoint(setReserveFloor)]
+    fn set_reserve_floor(&self, token: EgldOrEsdtTokenIdentifier, floor: BigUint) {
+        self.reserve_floor(&token).set(&floor);
+    }
+
+    #[view(getRemainingDailyCapacity)]
+    fn get_remaining_daily_capacity(
+        &self,
+        token: EgldOrEsdtTokenIdentifier,
+        user: ManagedAddress,
+    ) -> DailyCapacity<Self::Api> {
+        let limits = self.get_swap_limits(token.clone());
+        let user_volume = self.todays_volume(&self.user_daily_volume(&token, &user));
+        let global_volume = self.todays_volume(&self.global_daily_volume(&token));
+
+        DailyCapacity {
+            user_remaining: self.remaining_capacity(&limits.user_daily_cap, &user_volume),
+            global_remaining: self.remaining_capacity(&limits.global_daily_cap, &global_volume),
+        }
+    }
+
+    fn check_swap_limits(
+        &self,
+        user: &ManagedAddress,
+        token: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    ) -> Result<(), Refund<Self::Api>> {
+        let limits = self.get_swap_limits(token.clone());
+        if limits.max_per_swap > 0 && amount > &limits.max_per_swap {
+            return Result::Err(Refund::new(
+                RefundReason::SwapLimitExceeded,
+                "Amount exceeds the limit per swap".as_bytes().into(),
+            ));
+        }
+
+        let user_volume = self.todays_volume(&self.user_daily_volume(token, user));
+        if limits.user_daily_cap > 0 && user_volume + amount > limits.user_daily_cap {
+            return Result::Err(Refund::new(
+                RefundReason::DailyLimitExceeded,
+                "Amount exceeds the daily limit per user".as_bytes().into(),
+            ));
+        }
+
+        let global_volume = self.todays_volume(&self.global_daily_volume(token));
+        if limits.global_daily_cap > 0 && global_volume + amount > limits.global_daily_cap {
+            return Result::Err(Refund::new(
+                RefundReason::DailyLimitExceeded,
+                "Amount exceeds the daily limit of the exchange"
+                    .as_bytes()
+                    .into(),
+            ));
+        }
+
+        Result::Ok(())
+    }
+
+    fn check_reserve_floor(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+        balance: &BigUint,
+        amount_out: &BigUint,
+    ) -> Result<(), Refund<Self::Api>> {
+        let floor = self.reserve_floor(token).get();
+        if floor > 0 && balance < &(&floor + amount_out) {
+            return Result::Err(Refund::new(
+                RefundReason::ReserveFloorReached,
+                "Swap would take the reserve below its floor"
+                    .as_bytes()
+                    .into(),
+            ));
+        }
+
+        Result::Ok(())
+    }
+
+    fn record_swap_volume(
+        &self,
+        user: &ManagedAddress,
+        token: &EgldOrEsdtTokenIdentifier,
+        amount: &BigUint,
+    ) {
+        self.add_volume(&self.user_daily_volume(token, user), amount);
+        self.add_volume(&self.global_daily_volume(token), amount);
+    }
+
+    fn add_volume(&self, mapper: &SingleValueMapper<DailyVolume<Self::Api>>, amount: &BigUint) {
+        let volume = DailyVolume {
+            day: self.current_day(),
+            amount: self.todays_volume(mapper) + amount,
+        };
+        mapper.set(&volume);
+    }
+
+    fn todays_volume(&self, mapper: &SingleValueMapper<DailyVolume<Self::Api>>) -> BigUint {
+        if mapper.is_empty() {
+            return BigUint::zero();
+        }
+
+        let volume = mapper.get();
+        if volume.day == self.current_day() {
+            volume.amount
+        } else {
+            BigUint::zero()
+        }
+    }
+
+    fn remaining_capacity(&self, cap: &BigUint, volume: &BigUint) -> Option<BigUint> {
+        if cap == &0u32 {
+            None
+        } else if volume >= cap {
+            Some(BigUint::zero())
+        } else {
+            Some(cap - volume)
+        }
+    }
+
+    fn current_day(&self) -> u64 {
+        self.blockchain().get_block_timestamp() / SECONDS_PER_DAY
+    }
+
+    #[view(getSwapLimits)]
+    fn get_swap_limits(&self, token: EgldOrEsdtTokenIdentifier) -> SwapLimits<Self::Api> {
+        let mapper = self.swap_limits(&token);
+        if mapper.is_empty() {
+            SwapLimits {
+                max_per_swap: BigUint::zero(),
+                user_daily_cap: BigUint::zero(),
+                global_daily_cap: BigUint::zero(),
+            }
+        } else {
+            mapper.get()
+        }
+    }
+
+    #[storage_mapper("swap_limits")]
+    fn swap_limits(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<SwapLimits<Self::Api>>;
+
+    #[view(getReserveFloor)]
+    #[storage_mapper("reserve_floor")]
+    fn reserve_floor(&self, token: &EgldOrEsdtTokenIdentifier) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("user_daily_volume")]
+    fn user_daily_volume(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+        user: &ManagedAddress,
+    ) -> SingleValueMapper<DailyVolume<Self::Api>>;
+
+    #[storage_mapper("global_daily_volume")]
+    fn global_daily_volume(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+    ) -> SingleValueMapper<DailyVolume<Self::Api>>;
+}
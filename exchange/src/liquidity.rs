@@ -0,0 +1,275 @@
+elrond_wasm::imports!();
+
+/// Holds the exchange's liquidity as a single pool of every token it handles,
+/// owned by the liquidity providers in proportion to their shares. The fees and
+/// spreads kept by swaps stay in the pool, so they accrue to the providers.
+/// Providers join and leave with a slice of the whole pool rather than of a single
+/// token, so the tokens swapped into the pool are shared like the ones paid out of it.
+#[elrond_wasm::module]
+pub trait LiquidityModule {
+    /// Adds the payment to the pool and returns the shares minted for it.
+    /// The owner opens the pool with any of the handled tokens, and is credited
+    /// the sum of the amounts as shares. Afterwards a deposit has to include every
+    /// token held by the pool: it gets shares for the largest slice of the pool
+    /// that its amounts cover, and the rest of the payment is refunded.
+    #[payable("*")]
+    #[endpoint(addLiquidity)]
+    fn add_liquidity(&self) -> BigUint {
+        let egld_payment = self.call_value().egld_value();
+        let esdt_payments = self.call_value().all_esdt_transfers();
+        require!(
+            egld_payment > 0 || !esdt_payments.is_empty(),
+            "Payment must be more than 0"
+        );
+        for payment in esdt_payments.iter() {
+            require!(
+                payment.token_nonce == 0,
+                "Only fungible ESDT payments are accepted"
+            );
+            require!(payment.amount > 0, "Payment must be more than 0");
+        }
+
+        let caller = self.blockchain().get_caller();
+        let total_shares = self.total_lp_shares().get();
+        let shares = if total_shares == 0 {
+            require!(
+                caller == self.blockchain().get_owner_address(),
+                "Only the owner can open the pool"
+            );
+            let mut shares = egld_payment.clone();
+            for payment in esdt_payments.iter() {
+                shares += &payment.amount;
+            }
+            shares
+        } else {
+            self.require_pool_tokens(&egld_payment, &esdt_payments);
+            self.matching_shares(&egld_payment, &esdt_payments, &total_shares)
+        };
+        require!(shares > 0, "Deposit too small");
+
+        let mut refund_egld = BigUint::zero();
+        let mut refunds = ManagedVec::<Self::Api, EsdtTokenPayment<Self::Api>>::new();
+        if egld_payment > 0 {
+            let token = EgldOrEsdtTokenIdentifier::egld();
+            let taken = self.taken_amount(&token, &egld_payment, &shares, &total_shares);
+            refund_egld = &egld_payment - &taken;
+            self.increase_balance(&token, &taken);
+        }
+        for payment in esdt_payments.iter() {
+            let token = EgldOrEsdtTokenIdentifier::esdt(payment.token_identifier.clone());
+            if refunds
+                .iter()
+                .any(|refund| refund.token_identifier == payment.token_identifier)
+            {
+                // duplicates were already taken together with the first payment of the token
+                continue;
+            }
+            let paid = self.paid_amount(&token, &egld_payment, &esdt_payments);
+            let taken = self.taken_amount(&token, &paid, &shares, &total_shares);
+            self.increase_balance(&token, &taken);
+            refunds.push(EsdtTokenPayment::new(
+                payment.token_identifier,
+                0,
+                paid - taken,
+            ));
+        }
+
+        self.total_lp_shares().set(&(total_shares + &shares));
+        self.lp_shares(&caller)
+            .update(|provider_shares| *provider_shares += &shares);
+
+        if refund_egld > 0 {
+            self.send().direct_egld(&caller, &refund_egld);
+        }
+        let mut esdt_refunds = ManagedVec::<Self::Api, EsdtTokenPayment<Self::Api>>::new();
+        for refund in refunds.iter() {
+            if refund.amount > 0 {
+                esdt_refunds.push(refund);
+            }
+        }
+        if !esdt_refunds.is_empty() {
+            self.send().direct_multi(&caller, &esdt_refunds);
+        }
+
+        shares
+    }
+
+    /// Burns `shares` of the caller and sends them the same proportion of each
+    /// token held by the pool
+    #[endpoint(removeLiquidity)]
+    fn remove_liquidity(&self, shares: BigUint) {
+        require!(shares > 0, "Shares must be more than 0");
+        let caller = self.blockchain().get_caller();
+        let provider_shares = self.lp_shares(&caller).get();
+        require!(shares <= provider_shares, "Not enough liquidity shares");
+
+        let total_shares = self.total_lp_shares().get();
+        let mut egld_payout = BigUint::zero();
+        let mut esdt_payouts = ManagedVec::<Self::Api, EsdtTokenPayment<Self::Api>>::new();
+        for (token, balance) in self.balance().iter() {
+            let amount = balance * &shares / &total_shares;
+            if amount == 0 {
+                continue;
+            }
+            if token.is_egld() {
+                egld_payout = amount;
+            } else {
+                esdt_payouts.push(EsdtTokenPayment::new(token.unwrap_esdt(), 0, amount));
+            }
+        }
+
+        self.lp_shares(&caller).set(&(provider_shares - &shares));
+        self.total_lp_shares().set(&(total_shares - &shares));
+
+        if egld_payout > 0 {
+            self.decrease_balance(&EgldOrEsdtTokenIdentifier::egld(), &egld_payout);
+            self.send().direct_egld(&caller, &egld_payout);
+        }
+        for payout in esdt_payouts.iter() {
+            let token = EgldOrEsdtTokenIdentifier::esdt(payout.token_identifier);
+            self.decrease_balance(&token, &payout.amount);
+        }
+        if !esdt_payouts.is_empty() {
+            self.send().direct_multi(&caller, &esdt_payouts);
+        }
+    }
+
+    #[view(getBalances)]
+    fn get_balances(&self) -> MultiValueEncoded<MultiValue2<EgldOrEsdtTokenIdentifier, BigUint>> {
+        let mut result = MultiValueEncoded::new();
+        for (token, balance) in self.balance().iter() {
+            result.push((token, balance).into());
+        }
+        result
+    }
+
+    /// The tokens of the pool a provider's shares can currently be redeemed for
+    #[view(getLiquidityValue)]
+    fn get_liquidity_value(
+        &self,
+        provider: ManagedAddress,
+    ) -> MultiValueEncoded<MultiValue2<EgldOrEsdtTokenIdentifier, BigUint>> {
+        let shares = self.lp_shares(&provider).get();
+        let total_shares = self.total_lp_shares().get();
+        let mut result = MultiValueEncoded::new();
+        if shares == 0 {
+            return result;
+        }
+        for (token, balance) in self.balance().iter() {
+            result.push((token, balance * &shares / &total_shares).into());
+        }
+        result
+    }
+
+    /// Once the pool is open, deposits can only add to the tokens it holds, since
+    /// a token it does not hold has no share of the pool to be matched against
+    fn require_pool_tokens(
+        &self,
+        egld_payment: &BigUint,
+        esdt_payments: &ManagedVec<EsdtTokenPayment<Self::Api>>,
+    ) {
+        if *egld_payment > 0 {
+            require!(
+                self.pool_balance(&EgldOrEsdtTokenIdentifier::egld()) > 0,
+                "Token not held by the pool"
+            );
+        }
+        for payment in esdt_payments.iter() {
+            let token = EgldOrEsdtTokenIdentifier::esdt(payment.token_identifier);
+            require!(self.pool_balance(&token) > 0, "Token not held by the pool");
+        }
+    }
+
+    /// The shares of the largest slice of the pool covered by the payments, which
+    /// is limited by the token paid in the smallest proportion to its balance
+    fn matching_shares(
+        &self,
+        egld_payment: &BigUint,
+        esdt_payments: &ManagedVec<EsdtTokenPayment<Self::Api>>,
+        total_shares: &BigUint,
+    ) -> BigUint {
+        let mut shares: Option<BigUint> = None;
+        for (token, balance) in self.balance().iter() {
+            if balance == 0 {
+                continue;
+            }
+            let paid = self.paid_amount(&token, egld_payment, esdt_payments);
+            let token_shares = paid * total_shares / balance;
+            shares = match shares {
+                Some(shares) if shares <= token_shares => Some(shares),
+                _ => Some(token_shares),
+            };
+        }
+        shares.unwrap_or_else(|| BigUint::zero())
+    }
+
+    /// The part of `paid` needed to buy `shares` of the pool's balance of `token`,
+    /// rounded up in favour of the pool; all of it when the pool is being opened
+    fn taken_amount(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+        paid: &BigUint,
+        shares: &BigUint,
+        total_shares: &BigUint,
+    ) -> BigUint {
+        if *total_shares == 0 {
+            return paid.clone();
+        }
+        let balance = self.pool_balance(token);
+        (balance * shares + total_shares - 1u32) / total_shares
+    }
+
+    /// The total amount of `token` in the payments, which can repeat a token
+    fn paid_amount(
+        &self,
+        token: &EgldOrEsdtTokenIdentifier,
+        egld_payment: &BigUint,
+        esdt_payments: &ManagedVec<EsdtTokenPayment<Self::Api>>,
+    ) -> BigUint {
+        if token.is_egld() {
+            return egld_payment.clone();
+        }
+        let mut paid = BigUint::zero();
+        for payment in esdt_payments.iter() {
+            if token == &payment.token_identifier {
+                paid += &payment.amount;
+            }
+        }
+        paid
+    }
+
+    fn pool_balance(&self, token_identifier: &EgldOrEsdtTokenIdentifier) -> BigUint {
+        self.balance()
+            .get(token_identifier)
+            .unwrap_or_else(|| BigUint::zero())
+    }
+
+    fn increase_balance(&self, token_identifier: &EgldOrEsdtTokenIdentifier, amount: &BigUint) {
+        let mut balance = self
+            .balance()
+            .get(&token_identifier)
+            .unwrap_or_else(|| BigUint::zero());
+        balance += amount;
+        self.balance().insert(token_identifier.clone(), balance);
+    }
+
+    fn decrease_balance(&self, token_identifier: &EgldOrEsdtTokenIdentifier, amount: &BigUint) {
+        let mut balance = self
+            .balance()
+            .get(&token_identifier)
+            .unwrap_or_else(|| BigUint::zero());
+        balance -= amount;
+        self.balance().insert(token_identifier.clone(), balance);
+    }
+
+    #[storage_mapper("balance")]
+    fn balance(&self) -> MapMapper<EgldOrEsdtTokenIdentifier, BigUint>;
+
+    #[view(getLiquidityShares)]
+    #[storage_mapper("lp_shares")]
+    fn lp_shares(&self, provider: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[view(getTotalLiquidityShares)]
+    #[storage_mapper("total_lp_shares")]
+    fn total_lp_shares(&self) -> SingleValueMapper<BigUint>;
+}
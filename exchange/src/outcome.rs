@@ -0,0 +1,48 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+use crate::quote::RouteHop;
+
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum RefundReason {
+    PriceUnavailable,
+    InvalidRate,
+    BelowMinimumOutput,
+    InsufficientLiquidity,
+    SwapLimitExceeded,
+    DailyLimitExceeded,
+    ReserveFloorReached,
+    StalePrice,
+    RoundRegression,
+    RateChangeTooLarge,
+}
+
+/// Why an exchange could not be carried out, with a human-readable explanation
+pub struct Refund<M: ManagedTypeApi> {
+    pub reason: RefundReason,
+    pub message: ManagedBuffer<M>,
+}
+
+impl<M: ManagedTypeApi> Refund<M> {
+    pub fn new(reason: RefundReason, message: ManagedBuffer<M>) -> Self {
+        Refund { reason, message }
+    }
+}
+
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct SwapEvent<M: ManagedTypeApi> {
+    pub amount_in: BigUint<M>,
+    pub amount_out: BigUint<M>,
+    pub fee: BigUint<M>,
+    pub rate: BigUint<M>,
+    pub rate_decimals: u8,
+    pub route: ManagedVec<M, RouteHop<M>>,
+    pub message: ManagedBuffer<M>,
+}
+
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct RefundEvent<M: ManagedTypeApi> {
+    pub amount: BigUint<M>,
+    pub reason: RefundReason,
+    pub message: ManagedBuffer<M>,
+}
@@ -0,0 +1,26 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+/// One price feed of a route, read from the price-aggregator round `round_id`,
+/// with `decimals` decimals. `inverted` is set if the feed was published as `to/from`.
+#[derive(ManagedVecItem, TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone)]
+pub struct RouteHop<M: ManagedTypeApi> {
+    pub from: ManagedBuffer<M>,
+    pub to: ManagedBuffer<M>,
+    pub round_id: u32,
+    pub timestamp: u64,
+    pub decimals: u8,
+    pub inverted: bool,
+}
+
+/// The expected result of an exchange: the amount paid out, the fee kept from
+/// the converted amount and the rate used, after the pair's spread, as a fixed-point
+/// number with `rate_decimals` decimals, composed from the price feeds of `route`
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi)]
+pub struct Quote<M: ManagedTypeApi> {
+    pub amount_out: BigUint<M>,
+    pub fee: BigUint<M>,
+    pub rate: BigUint<M>,
+    pub rate_decimals: u8,
+    pub route: ManagedVec<M, RouteHop<M>>,
+}
@@ -0,0 +1,97 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+/// An exchange rate as a fraction: `amount` of the source token is worth
+/// `amount * numerator / denominator` of the target token
+#[derive(TopEncode, TopDecode, TypeAbi, Clone)]
+pub struct Rate<M: ManagedTypeApi> {
+    pub numerator: BigUint<M>,
+    pub denominator: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> Rate<M> {
+    /// The rate given by a price feed with `decimals` decimals
+    pub fn from_price(price: BigUint<M>, decimals: u8) -> Self {
+        Rate {
+            numerator: price,
+            denominator: BigUint::from(10u32).pow(decimals as u32),
+        }
+    }
+
+    pub fn identity() -> Self {
+        Rate {
+            numerator: BigUint::from(1u32),
+            denominator: BigUint::from(1u32),
+        }
+    }
+
+    /// The rate of converting at this rate and then at `next`. The fractions are
+    /// multiplied exactly, so rounding only happens once, when converting.
+    pub fn then(self, next: Rate<M>) -> Self {
+        Rate {
+            numerator: self.numerator * next.numerator,
+            denominator: self.denominator * next.denominator,
+        }
+    }
+
+    pub fn inverse(self) -> Self {
+        Rate {
+            numerator: self.denominator,
+            denominator: self.numerator,
+        }
+    }
+
+    /// Turns a rate between whole tokens into a rate between the tokens' smallest
+    /// units, e.g. for a rate from EGLD (18 decimals) to a 6 decimals stablecoin
+    /// the result is 10^12 times smaller
+    pub fn between_units(self, source_decimals: u8, target_decimals: u8) -> Self {
+        if target_decimals >= source_decimals {
+            let scale = BigUint::from(10u32).pow((target_decimals - source_decimals) as u32);
+            Rate {
+                numerator: self.numerator * scale,
+                denominator: self.denominator,
+            }
+        } else {
+            let scale = BigUint::from(10u32).pow((source_decimals - target_decimals) as u32);
+            Rate {
+                numerator: self.numerator,
+                denominator: self.denominator * scale,
+            }
+        }
+    }
+
+    /// This rate reduced by `bps` basis points
+    pub fn less_bps(self, bps: u32) -> Self {
+        self.then(Rate {
+            numerator: BigUint::from(10_000u32 - bps),
+            denominator: BigUint::from(10_000u32),
+        })
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.numerator > 0u32 && self.denominator > 0u32
+    }
+
+    /// Whether this rate differs from `previous` by more than `max_change_bps`
+    /// basis points of `previous`
+    pub fn change_exceeds(&self, previous: &Rate<M>, max_change_bps: u32) -> bool {
+        // compares a/b to c/d as a*d to c*b
+        let current = &self.numerator * &previous.denominator;
+        let reference = &previous.numerator * &self.denominator;
+        let change = if current > reference {
+            current - &reference
+        } else {
+            &reference - &current
+        };
+        change * BigUint::from(10_000u32) > reference * BigUint::from(max_change_bps)
+    }
+
+    pub fn convert(&self, amount: &BigUint<M>) -> BigUint<M> {
+        amount * &self.numerator / &self.denominator
+    }
+
+    /// The rate as a fixed-point number with `decimals` decimals, rounded down
+    pub fn to_fixed_point(&self, decimals: u8) -> BigUint<M> {
+        self.convert(&BigUint::from(10u32).pow(decimals as u32))
+    }
+}
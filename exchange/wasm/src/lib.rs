@@ -7,8 +7,41 @@
 elrond_wasm_node::wasm_endpoints! {
     exchange
     (
-        callBack
-        deposit
+        addLiquidity
         exchange
+        exchangeMulti
+        getAggregatorAddress
+        getBalances
+        getLiquidityShares
+        getLiquidityValue
+        getMaxPriceAge
+        getMaxRateChange
+        getMaxReferenceAge
+        getPairFee
+        getPairSpread
+        getRemainingDailyCapacity
+        getReserveFloor
+        getRoute
+        getSwapLimits
+        getTokenDecimals
+        getTokenTickers
+        getTotalLiquidityShares
+        quote
+        removeLiquidity
+        removeRoute
+        removeTokenTicker
+        resetReferenceRate
+        setMaxPriceAge
+        setMaxRateChange
+        setMaxReferenceAge
+        setPairFee
+        setPairSpread
+        setPriceAggregatorAddress
+        setReserveFloor
+        setRoute
+        setSwapLimits
+        setTokenTicker
     )
 }
+
+elrond_wasm_node::wasm_empty_callback! {}
@@ -0,0 +1,137 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The largest power of 10 that fits in a `u32`, used as the base of the
+/// intermediate representation
+const LIMB_BASE: u64 = 1_000_000_000;
+const LIMB_DIGITS: usize = 9;
+
+/// The largest magnitude, in bytes, that can be formatted without allocating
+pub const MAX_MAGNITUDE_LEN: usize = 128;
+
+/// 2^1024 is less than 10^315
+const MAX_LIMBS: usize = 35;
+
+/// The decimal digits of a magnitude of at most `MAX_MAGNITUDE_LEN` bytes,
+/// computed on the stack
+pub struct DecimalDigits {
+    digits: [u8; MAX_LIMBS * LIMB_DIGITS],
+    start: usize,
+}
+
+impl DecimalDigits {
+    /// Converts a big-endian magnitude into its ASCII decimal digits, without
+    /// leading zeros; zero is `"0"`. Returns `None` if the magnitude is too long.
+    pub fn new(magnitude_be: &[u8]) -> Option<Self> {
+        if magnitude_be.len() > MAX_MAGNITUDE_LEN {
+            return None;
+        }
+
+        // little-endian limbs in base 10^9
+        let mut limbs = [0u32; MAX_LIMBS];
+        let mut limbs_len = 0;
+        for &byte in magnitude_be {
+            let mut carry = byte as u64;
+            for limb in limbs[..limbs_len].iter_mut() {
+                let value = (*limb as u64) * 256 + carry;
+                *limb = (value % LIMB_BASE) as u32;
+                carry = value / LIMB_BASE;
+            }
+            while carry > 0 {
+                limbs[limbs_len] = (carry % LIMB_BASE) as u32;
+                limbs_len += 1;
+                carry /= LIMB_BASE;
+            }
+        }
+
+        let mut digits = [b'0'; MAX_LIMBS * LIMB_DIGITS];
+        for (index, limb) in limbs[..limbs_len].iter().enumerate() {
+            let end = digits.len() - index * LIMB_DIGITS;
+            write_limb(&mut digits[end - LIMB_DIGITS..end], *limb);
+        }
+        let start = digits
+            .iter()
+            .position(|digit| *digit != b'0')
+            .unwrap_or(digits.len() - 1);
+        Some(DecimalDigits { digits, start })
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.digits[self.start..]
+    }
+}
+
+/// Writes the nine digits of a limb, padded with zeros
+fn write_limb(buffer: &mut [u8], mut limb: u32) {
+    for digit in buffer.iter_mut().rev() {
+        *digit = b'0' + (limb % 10) as u8;
+        limb /= 10;
+    }
+}
+
+/// Converts a big-endian magnitude into its ASCII decimal digits, without
+/// leading zeros; zero is `"0"`
+#[cfg(feature = "alloc")]
+pub fn to_decimal_digits(magnitude_be: &[u8]) -> Vec<u8> {
+    // little-endian limbs in base 10^9
+    let mut limbs: Vec<u32> = Vec::new();
+    for &byte in magnitude_be {
+        let mut carry = byte as u64;
+        for limb in limbs.iter_mut() {
+            let value = (*limb as u64) * 256 + carry;
+            *limb = (value % LIMB_BASE) as u32;
+            carry = value / LIMB_BASE;
+        }
+        while carry > 0 {
+            limbs.push((carry % LIMB_BASE) as u32);
+            carry /= LIMB_BASE;
+        }
+    }
+
+    let mut digits = Vec::with_capacity(limbs.len() * LIMB_DIGITS + 1);
+    match limbs.split_last() {
+        None => digits.push(b'0'),
+        Some((most_significant, rest)) => {
+            push_limb(&mut digits, *most_significant, false);
+            for limb in rest.iter().rev() {
+                push_limb(&mut digits, *limb, true);
+            }
+        }
+    }
+    digits
+}
+
+#[cfg(feature = "alloc")]
+fn push_limb(digits: &mut Vec<u8>, mut limb: u32, padded: bool) {
+    let mut buffer = [b'0'; LIMB_DIGITS];
+    let mut len = 0;
+    while limb > 0 {
+        buffer[LIMB_DIGITS - 1 - len] = b'0' + (limb % 10) as u8;
+        limb /= 10;
+        len += 1;
+    }
+    let start = if padded { 0 } else { LIMB_DIGITS - len };
+    digits.extend_from_slice(&buffer[start..]);
+}
+
+/// Converts ASCII decimal digits into a big-endian magnitude, without leading
+/// zero bytes; zero is the empty magnitude. All bytes must be digits.
+#[cfg(feature = "alloc")]
+pub fn from_decimal_digits(digits: &[u8]) -> Vec<u8> {
+    // little-endian bytes
+    let mut bytes: Vec<u8> = Vec::new();
+    for &digit in digits {
+        let mut carry = (digit - b'0') as u32;
+        for byte in bytes.iter_mut() {
+            let value = (*byte as u32) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry > 0 {
+            bytes.push(carry as u8);
+        }
+    }
+
+    bytes.reverse();
+    bytes
+}
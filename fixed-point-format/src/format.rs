@@ -0,0 +1,225 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "alloc")]
+use crate::digits::to_decimal_digits;
+use crate::digits::DecimalDigits;
+use crate::{DECIMAL_POINT, EXPONENT_MARKER};
+
+/// Where the formatted bytes are written
+pub trait Sink {
+    fn write(&mut self, bytes: &[u8]);
+}
+
+impl<S: Sink + ?Sized> Sink for &mut S {
+    fn write(&mut self, bytes: &[u8]) {
+        (**self).write(bytes)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Sink for Vec<u8> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+/// The magnitude is longer than `MAX_MAGNITUDE_LEN` bytes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MagnitudeTooLarge;
+
+/// How a fixed-point number is written
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Format {
+    /// The number of decimals of the magnitude
+    pub decimals: usize,
+    /// Inserted between groups of three digits of the integer part
+    pub thousands_separator: Option<u8>,
+    /// Drops the trailing zeros of the fractional part, and the decimal point
+    /// if no fractional digits are left
+    pub trim_trailing_zeros: bool,
+    /// Numbers whose integer part has more digits than this are written in
+    /// scientific notation, as `d.ddde<exponent>` with no trailing zeros
+    pub scientific_above_digits: Option<usize>,
+}
+
+impl Format {
+    /// Plain integers, with no decimals
+    pub const fn integer() -> Self {
+        Format {
+            decimals: 0,
+            thousands_separator: None,
+            trim_trailing_zeros: false,
+            scientific_above_digits: None,
+        }
+    }
+
+    /// All `decimals` fractional digits, always followed by a decimal point
+    pub const fn fixed(decimals: usize) -> Self {
+        Format {
+            decimals,
+            thousands_separator: None,
+            trim_trailing_zeros: false,
+            scientific_above_digits: None,
+        }
+    }
+
+    pub const fn with_thousands_separator(mut self, separator: u8) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    pub const fn with_trimmed_zeros(mut self) -> Self {
+        self.trim_trailing_zeros = true;
+        self
+    }
+
+    pub const fn with_scientific_above(mut self, digits: usize) -> Self {
+        self.scientific_above_digits = Some(digits);
+        self
+    }
+}
+
+/// Writes the number with the big-endian magnitude `magnitude_be` to `sink`,
+/// without allocating
+pub fn write_unsigned<S: Sink>(
+    magnitude_be: &[u8],
+    format: &Format,
+    sink: &mut S,
+) -> Result<(), MagnitudeTooLarge> {
+    let digits = DecimalDigits::new(magnitude_be).ok_or(MagnitudeTooLarge)?;
+    write_digits(sink, digits.as_slice(), format);
+    Result::Ok(())
+}
+
+/// Like `write_unsigned`, preceded by a minus sign if the number is negative and not zero
+pub fn write_signed<S: Sink>(
+    negative: bool,
+    magnitude_be: &[u8],
+    format: &Format,
+    sink: &mut S,
+) -> Result<(), MagnitudeTooLarge> {
+    let digits = DecimalDigits::new(magnitude_be).ok_or(MagnitudeTooLarge)?;
+    if negative && !is_zero(magnitude_be) {
+        sink.write(b"-");
+    }
+    write_digits(sink, digits.as_slice(), format);
+    Result::Ok(())
+}
+
+/// Writes the number with the big-endian magnitude `magnitude_be`
+#[cfg(feature = "alloc")]
+pub fn format_unsigned(magnitude_be: &[u8], format: &Format) -> Vec<u8> {
+    let mut result = Vec::new();
+    write_digits(&mut result, &to_decimal_digits(magnitude_be), format);
+    result
+}
+
+/// Writes the number with the big-endian magnitude `magnitude_be`, preceded by
+/// a minus sign if it is negative and not zero
+#[cfg(feature = "alloc")]
+pub fn format_signed(negative: bool, magnitude_be: &[u8], format: &Format) -> Vec<u8> {
+    let mut result = Vec::new();
+    if negative && !is_zero(magnitude_be) {
+        result.push(b'-');
+    }
+    write_digits(&mut result, &to_decimal_digits(magnitude_be), format);
+    result
+}
+
+fn is_zero(magnitude_be: &[u8]) -> bool {
+    magnitude_be.iter().all(|byte| *byte == 0)
+}
+
+fn write_digits<S: Sink>(sink: &mut S, digits: &[u8], format: &Format) {
+    let integer_digits = digits.len().saturating_sub(format.decimals);
+    let is_zero = digits == b"0";
+
+    match format.scientific_above_digits {
+        Some(limit) if integer_digits > limit && !is_zero => {
+            write_scientific(sink, digits, integer_digits - 1)
+        }
+        _ => write_fixed(sink, digits, format),
+    }
+}
+
+fn write_fixed<S: Sink>(sink: &mut S, digits: &[u8], format: &Format) {
+    let padding = (format.decimals + 1).saturating_sub(digits.len());
+    let padded_len = digits.len() + padding;
+    let integer_len = padded_len - format.decimals;
+    let digit_at = |index: usize| {
+        if index < padding {
+            b'0'
+        } else {
+            digits[index - padding]
+        }
+    };
+
+    match format.thousands_separator {
+        None => write_padded(sink, digits, padding, 0, integer_len),
+        Some(separator) => {
+            // the first group of three digits is shorter
+            let mut group_start = 0;
+            let mut group_end = match integer_len % 3 {
+                0 => 3,
+                len => len,
+            };
+            while group_start < integer_len {
+                if group_start > 0 {
+                    sink.write(&[separator]);
+                }
+                write_padded(sink, digits, padding, group_start, group_end);
+                group_start = group_end;
+                group_end += 3;
+            }
+        }
+    }
+
+    let mut fraction_end = padded_len;
+    if format.trim_trailing_zeros {
+        while fraction_end > integer_len && digit_at(fraction_end - 1) == b'0' {
+            fraction_end -= 1;
+        }
+        if fraction_end == integer_len {
+            return;
+        }
+    } else if format.decimals == 0 {
+        return;
+    }
+
+    sink.write(&[DECIMAL_POINT]);
+    write_padded(sink, digits, padding, integer_len, fraction_end);
+}
+
+/// Writes the digits from `start` to `end` of the number preceded by `padding` zeros,
+/// in as few writes as possible
+fn write_padded<S: Sink>(sink: &mut S, digits: &[u8], padding: usize, start: usize, end: usize) {
+    const ZEROS: [u8; 32] = [b'0'; 32];
+    let zeros_end = end.min(padding);
+    let mut position = start;
+    while position < zeros_end {
+        let len = (zeros_end - position).min(ZEROS.len());
+        sink.write(&ZEROS[..len]);
+        position += len;
+    }
+    if position < end {
+        sink.write(&digits[position - padding..end - padding]);
+    }
+}
+
+fn write_scientific<S: Sink>(sink: &mut S, digits: &[u8], exponent: usize) {
+    let mut significant_len = digits.len();
+    while significant_len > 1 && digits[significant_len - 1] == b'0' {
+        significant_len -= 1;
+    }
+
+    sink.write(&digits[..1]);
+    if significant_len > 1 {
+        sink.write(&[DECIMAL_POINT]);
+        sink.write(&digits[1..significant_len]);
+    }
+    sink.write(&[EXPONENT_MARKER]);
+    if let Some(exponent_digits) = DecimalDigits::new(&(exponent as u64).to_be_bytes()) {
+        sink.write(exponent_digits.as_slice());
+    }
+}
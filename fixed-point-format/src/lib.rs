@@ -0,0 +1,32 @@
+//! Formatting and parsing of arbitrary-size fixed-point numbers, for building
+//! human-readable messages in contracts.
+//!
+//! Numbers are given as their big-endian magnitude bytes (as returned by
+//! `BigUint::to_bytes_be`) together with the number of decimals, so a
+//! magnitude of `12345` with 3 decimals stands for `12.345`.
+//!
+//! `write_unsigned` and `write_signed` write to a `Sink` without allocating,
+//! for magnitudes of at most `MAX_MAGNITUDE_LEN` bytes. The functions returning
+//! vectors, and parsing, need the `alloc` feature, enabled by default.
+
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod digits;
+mod format;
+#[cfg(feature = "alloc")]
+mod parse;
+
+#[cfg(feature = "alloc")]
+pub use digits::{from_decimal_digits, to_decimal_digits};
+pub use digits::{DecimalDigits, MAX_MAGNITUDE_LEN};
+#[cfg(feature = "alloc")]
+pub use format::{format_signed, format_unsigned};
+pub use format::{write_signed, write_unsigned, Format, MagnitudeTooLarge, Sink};
+#[cfg(feature = "alloc")]
+pub use parse::{parse_signed, parse_unsigned, ParseError};
+
+pub const DECIMAL_POINT: u8 = b'.';
+pub const EXPONENT_MARKER: u8 = b'e';
@@ -0,0 +1,110 @@
+use alloc::vec::Vec;
+
+use crate::digits::from_decimal_digits;
+use crate::format::Format;
+use crate::{DECIMAL_POINT, EXPONENT_MARKER};
+
+/// Exponents are capped so that parsing cannot be made to allocate without bound
+const MAX_EXPONENT: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParseError {
+    Empty,
+    InvalidCharacter,
+    /// The number has more fractional digits than the format's decimals
+    TooManyDecimals,
+    ExponentTooLarge,
+    /// A minus sign was found when parsing an unsigned number
+    Negative,
+}
+
+/// Reads a number written in `format`, or in any other format with the same
+/// decimals and thousands separator, as a big-endian magnitude scaled by
+/// `10^format.decimals`. Zero is the empty magnitude.
+pub fn parse_unsigned(input: &[u8], format: &Format) -> Result<Vec<u8>, ParseError> {
+    match parse_signed(input, format)? {
+        (true, magnitude) if !magnitude.is_empty() => Result::Err(ParseError::Negative),
+        (_, magnitude) => Result::Ok(magnitude),
+    }
+}
+
+/// Like `parse_unsigned`, also accepting a leading minus sign. Returns whether
+/// the number is negative, and its magnitude.
+pub fn parse_signed(input: &[u8], format: &Format) -> Result<(bool, Vec<u8>), ParseError> {
+    let (negative, input) = match input.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        _ => (false, input),
+    };
+    let (mantissa, exponent) = match input.iter().position(|byte| *byte == EXPONENT_MARKER) {
+        Some(position) => (&input[..position], parse_exponent(&input[position + 1..])?),
+        None => (input, 0),
+    };
+
+    let (integer_part, fraction_part) =
+        match mantissa.iter().position(|byte| *byte == DECIMAL_POINT) {
+            Some(position) => (&mantissa[..position], &mantissa[position + 1..]),
+            None => (mantissa, &mantissa[mantissa.len()..]),
+        };
+
+    let mut digits = Vec::with_capacity(mantissa.len() + format.decimals);
+    for &byte in integer_part {
+        if Some(byte) == format.thousands_separator && !digits.is_empty() {
+            continue;
+        }
+        push_digit(&mut digits, byte)?;
+    }
+    for &byte in fraction_part {
+        push_digit(&mut digits, byte)?;
+    }
+    if digits.is_empty() {
+        return Result::Err(ParseError::Empty);
+    }
+
+    // the value is digits * 10^(exponent - fraction_len), scaled by 10^decimals
+    let shift = exponent + format.decimals as i64 - fraction_part.len() as i64;
+    if shift >= 0 {
+        digits.resize(digits.len() + shift as usize, b'0');
+    } else {
+        let dropped = (-shift) as usize;
+        let kept = digits.len().saturating_sub(dropped);
+        if digits[kept..].iter().any(|digit| *digit != b'0') {
+            return Result::Err(ParseError::TooManyDecimals);
+        }
+        digits.truncate(kept);
+    }
+
+    Result::Ok((negative, from_decimal_digits(&digits)))
+}
+
+fn push_digit(digits: &mut Vec<u8>, byte: u8) -> Result<(), ParseError> {
+    if !byte.is_ascii_digit() {
+        return Result::Err(ParseError::InvalidCharacter);
+    }
+    digits.push(byte);
+    Result::Ok(())
+}
+
+fn parse_exponent(input: &[u8]) -> Result<i64, ParseError> {
+    let (negative, digits) = match input.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        Some((b'+', rest)) => (false, rest),
+        _ => (false, input),
+    };
+    if digits.is_empty() {
+        return Result::Err(ParseError::Empty);
+    }
+
+    let mut exponent = 0usize;
+    for &byte in digits {
+        if !byte.is_ascii_digit() {
+            return Result::Err(ParseError::InvalidCharacter);
+        }
+        exponent = exponent * 10 + (byte - b'0') as usize;
+        if exponent > MAX_EXPONENT {
+            return Result::Err(ParseError::ExponentTooLarge);
+        }
+    }
+
+    let exponent = exponent as i64;
+    Result::Ok(if negative { -exponent } else { exponent })
+}
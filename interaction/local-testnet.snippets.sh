@@ -200,6 +200,23 @@ send_esdt_with_call() {
         --gas-limit=500000000 --data "ESDTTransfer@$TOKEN_ID_HEX@$AMOUNT_HEX@$OTHER_ARGUMENTS" --send)
 }
 
+send_two_esdt_with_call() {
+    local SOURCE=$1
+    local DESTINATION_ADDRESS=$2
+    local TOKEN_ID_1=$3
+    local AMOUNT_1=$4
+    local TOKEN_ID_2=$5
+    local AMOUNT_2=$6
+    local OTHER_ARGUMENTS=${@:7}
+
+    local SOURCE_WALLET=$(wallet_of $SOURCE)
+    local DESTINATION_HEX=$(bech_to_hex $DESTINATION_ADDRESS)
+    local PAYMENT_1="$(ascii_to_hex $TOKEN_ID_1)@00@$(number_to_hex $AMOUNT_1)"
+    local PAYMENT_2="$(ascii_to_hex $TOKEN_ID_2)@00@$(number_to_hex $AMOUNT_2)"
+    (set -x; erdpy --verbose tx new --recall-nonce --pem=${SOURCE_WALLET} --receiver $(address_of $SOURCE) \
+        --gas-limit=500000000 --data "MultiESDTNFTTransfer@$DESTINATION_HEX@02@$PAYMENT_1@$PAYMENT_2@$OTHER_ARGUMENTS" --send)
+}
+
 check_issued_tokens() {
     call $METACHAIN_ADDRESS eve getAllESDTTokens 0
 }
@@ -271,11 +288,9 @@ step_4_prepare_aggregator() {
 }
 
 step_5_prepare_exchange() {
-    send_esdt_with_call eve ${EXCHANGE_EVE_ADDRESS} $TOKEN_A $SOME_TOKENS "$(ascii_to_hex deposit)"
-    
-    sleep 6
-
-    send_esdt_with_call eve ${EXCHANGE_EVE_ADDRESS} $TOKEN_B $SOME_TOKENS "$(ascii_to_hex deposit)"
+    # eve, the owner of the exchange, opens its pool with both tokens
+    send_two_esdt_with_call eve ${EXCHANGE_EVE_ADDRESS} $TOKEN_A $SOME_TOKENS $TOKEN_B $SOME_TOKENS \
+        "$(ascii_to_hex addLiquidity)"
 }
 
 step_6_send_funds_to_other_users() {
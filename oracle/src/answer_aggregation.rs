@@ -0,0 +1,41 @@
+elrond_wasm::imports!();
+
+use crate::job::AnswerType;
+use aggregator::{aggregator_interface::SingleSubmissionValuesVec, median};
+
+/// Combines the answers of the nodes which fulfilled a request into a single answer:
+/// the median for unsigned answers, the answer of a strict majority of the nodes otherwise.
+pub fn aggregate<M: ManagedTypeApi>(
+    answer_type: AnswerType,
+    answers: &ManagedVec<M, ManagedBuffer<M>>,
+) -> Result<ManagedBuffer<M>, StaticSCError> {
+    match answer_type {
+        AnswerType::Unsigned => {
+            let mut values = SingleSubmissionValuesVec::new();
+            for answer in answers.iter() {
+                values
+                    .try_push(BigUint::from_bytes_be_buffer(&answer))
+                    .map_err(|_| StaticSCError::from("too many answers"))?;
+            }
+            let median = median::calculate(values)?.ok_or("no answers")?;
+            Result::Ok(median.to_bytes_be_buffer())
+        }
+        AnswerType::Bytes | AnswerType::Boolean => most_common(answers),
+    }
+}
+
+/// Returns the answer given by more than half of the nodes
+pub fn most_common<M: ManagedTypeApi>(
+    answers: &ManagedVec<M, ManagedBuffer<M>>,
+) -> Result<ManagedBuffer<M>, StaticSCError> {
+    if answers.is_empty() {
+        return Result::Err("no answers".into());
+    }
+    for answer in answers.iter() {
+        let count = answers.iter().filter(|other| **other == *answer).count();
+        if count * 2 > answers.len() {
+            return Result::Ok((*answer).clone());
+        }
+    }
+    Result::Err("no majority answer".into())
+}
@@ -0,0 +1,17 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+/// The kind of answer the nodes handling a job are expected to provide
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum AnswerType {
+    Bytes,
+    Unsigned,
+    Boolean,
+}
+
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct JobSpec<M: ManagedTypeApi> {
+    pub description: ManagedBuffer<M>,
+    pub fee: BigUint<M>,
+    pub answer_type: AnswerType,
+}
@@ -1,42 +1,79 @@
 #![no_std]
 
 extern crate aggregator;
-mod oracle_request;
-use oracle_request::{OracleRequest, RequestView};
+pub mod answer_aggregation;
+pub mod job;
+pub mod oracle_request;
+pub mod relay;
+use aggregator::aggregator_interface::MAX_SUBMISSION_VALUES;
+use job::{AnswerType, JobSpec};
+use oracle_request::{
+    request_id_preimage, DeliveryStatus, FailedDelivery, OracleRequest, RequestId, RequestView,
+};
+use price_aggregator_proxy::PriceSubmission;
+use relay::{RelayOutcome, RelayResult, RelaySubmission, RELAY_HISTORY_LEN};
 
 elrond_wasm::imports!();
 elrond_wasm::derive_imports!();
 
-mod client_proxy {
-    elrond_wasm::imports!();
-    #[elrond_wasm::derive::proxy]
-    pub trait Client {
-        #[endpoint]
-        fn reply(&self, nonce: u64, answer: ManagedBuffer);
-    }
-}
+static PAUSED_ERROR_MSG: &[u8] = b"Contract is paused";
+
+/// Requests can be cancelled by their caller after this many seconds
+pub const EXPIRY_TIME: u64 = 5 * 60;
+
+/// Upper bound of the gas a request can reserve for its callback
+pub const MAX_CALLBACK_GAS_LIMIT: u64 = 100_000_000;
+
+/// Gas a node has to provide on top of the callback gas limit of a request,
+/// for recording the outcome of the delivery
+pub const DELIVERY_GAS_OVERHEAD: u64 = 10_000_000;
+
+/// Upper bound of the size of the CBOR encoded parameters of a request
+pub const MAX_REQUEST_DATA_LEN: usize = 1024;
 
 #[elrond_wasm::contract]
-pub trait Oracle {
+pub trait Oracle: elrond_wasm_modules::pause::PauseModule {
+    /// The last nonce used by each caller
+    #[view(lastNonces)]
     #[storage_mapper("nonces")]
     fn nonces(&self) -> MapMapper<ManagedAddress, u64>;
 
     #[storage_mapper("requests")]
-    fn requests(
-        &self,
-    ) -> MapStorageMapper<ManagedAddress, MapMapper<u64, OracleRequest<Self::Api>>>;
+    fn requests(&self) -> MapMapper<RequestId<Self::Api>, OracleRequest<Self::Api>>;
 
+    /// The ids of the requests which did not expire yet, each followed by the request
     #[view(requestsAsVec)]
-    fn requests_as_vec(&self) -> MultiValueEncoded<RequestView<Self::Api>> {
+    fn requests_as_vec(
+        &self,
+    ) -> MultiValueEncoded<MultiValue2<RequestId<Self::Api>, RequestView<Self::Api>>> {
+        self.filter_requests(false)
+    }
+
+    #[view(expiredRequests)]
+    fn expired_requests(
+        &self,
+    ) -> MultiValueEncoded<MultiValue2<RequestId<Self::Api>, RequestView<Self::Api>>> {
+        self.filter_requests(true)
+    }
+
+    fn filter_requests(
+        &self,
+        expired: bool,
+    ) -> MultiValueEncoded<MultiValue2<RequestId<Self::Api>, RequestView<Self::Api>>> {
+        let current_timestamp = self.blockchain().get_block_timestamp();
         let mut vec = MultiValueEncoded::new();
-        for (address, request) in self.requests().iter() {
-            for (nonce, oracle_request) in request.iter() {
-                vec.push(RequestView {
-                    address: address.clone(),
-                    nonce,
-                    data: oracle_request.data,
-                })
+        for (request_id, oracle_request) in self.requests().iter() {
+            if oracle_request.is_expired(current_timestamp) != expired {
+                continue;
             }
+            let view = RequestView {
+                address: oracle_request.caller,
+                nonce: oracle_request.nonce,
+                job_id: oracle_request.job_id,
+                data: oracle_request.data,
+                threshold: oracle_request.threshold,
+            };
+            vec.push((request_id, view).into());
         }
 
         vec
@@ -46,83 +83,523 @@ pub trait Oracle {
     #[storage_mapper("authorized_nodes")]
     fn authorized_nodes(&self) -> SetMapper<ManagedAddress>;
 
+    #[view(getGuardian)]
+    #[storage_mapper("guardian")]
+    fn guardian(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getFeeToken)]
+    #[storage_mapper("fee_token")]
+    fn fee_token(&self) -> SingleValueMapper<EgldOrEsdtTokenIdentifier>;
+
+    #[storage_mapper("jobs")]
+    fn jobs(&self) -> MapMapper<ManagedBuffer, JobSpec<Self::Api>>;
+
+    /// The nodes allowed to fulfill the requests of a job
+    #[view(jobNodes)]
+    #[storage_mapper("job_nodes")]
+    fn job_nodes(&self, job_id: &ManagedBuffer) -> SetMapper<ManagedAddress>;
+
+    #[view(deliveryStatus)]
+    #[storage_mapper("delivery_status")]
+    fn delivery_status(
+        &self,
+        request_id: &RequestId<Self::Api>,
+    ) -> SingleValueMapper<DeliveryStatus>;
+
+    /// Answers whose callback failed, by the id of the request, for each requester
+    #[view(failedDeliveries)]
+    #[storage_mapper("failed_deliveries")]
+    fn failed_deliveries(
+        &self,
+        caller: &ManagedAddress,
+    ) -> MapMapper<RequestId<Self::Api>, FailedDelivery<Self::Api>>;
+
+    /// The answers submitted so far by the nodes, for requests with a threshold above one
+    #[storage_mapper("request_answers")]
+    fn request_answers(
+        &self,
+        request_id: &RequestId<Self::Api>,
+    ) -> MapMapper<ManagedAddress, ManagedBuffer>;
+
+    /// The latest submissions relayed to an aggregator or a price-aggregator,
+    /// at most `RELAY_HISTORY_LEN`, overwritten from the oldest one
+    #[storage_mapper("relay_results")]
+    fn relay_results(&self, target: &ManagedAddress) -> VecMapper<RelayResult<Self::Api>>;
+
+    /// The number of submissions ever relayed to a target
+    #[storage_mapper("relay_count")]
+    fn relay_count(&self, target: &ManagedAddress) -> SingleValueMapper<u64>;
+
+    /// Fees earned by the nodes for fulfilled requests
+    #[view(withdrawableFees)]
+    #[storage_mapper("withdrawable_fees")]
+    fn withdrawable_fees(&self, node: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
     #[init]
-    fn init(&self) {}
+    fn init(&self, fee_token: EgldOrEsdtTokenIdentifier) {
+        self.fee_token().set(&fee_token);
+    }
 
-    /// This is the entry point that will use the escrow transfer_from.
-    /// Afterwards, it essentially calls itself (store_request) which stores the request in state.
+    /// Registers a request for the given job, to be handled off-chain by one of the job's nodes,
+    /// and returns its id. The nonce must be greater than the last one of the caller.
+    /// The fee paid with the request is held in escrow until the request is fulfilled.
+    /// The answer is delivered by calling `callback_method(request_id, nonce, answer)`
+    /// on `callback_address`. The asynchronous call gets all the gas left in the fulfilling
+    /// transaction, which must be at least `callback_gas_limit`.
+    /// With a `threshold` of M, the answers of M different nodes of the job are aggregated
+    /// into the one delivered; a single answer is enough by default.
+    /// `data` holds the parameters of the request, as CBOR encoded key/value pairs.
+    #[payable("*")]
     #[endpoint(request)]
     fn request(
         &self,
+        job_id: ManagedBuffer,
         callback_address: ManagedAddress,
         callback_method: ManagedBuffer,
+        callback_gas_limit: u64,
         nonce: u64,
         data: ManagedBuffer,
-    ) {
-        let caller = self.blockchain().get_caller();
-        let mut requests = self.requests();
-        let mut caller_requests = requests.entry(caller.clone()).or_default().get();
-
-        // Ensure there isn't already the same nonce
+        threshold: OptionalValue<u32>,
+    ) -> RequestId<Self::Api> {
+        self.require_not_paused();
+        require!(!callback_method.is_empty(), "Empty callback method");
+        require!(
+            callback_gas_limit > 0 && callback_gas_limit <= MAX_CALLBACK_GAS_LIMIT,
+            "Invalid callback gas limit"
+        );
+        self.require_valid_request_data(&data);
+        let job = self.get_job(&job_id);
+        let (fee_token, fee) = self.call_value().egld_or_single_fungible_esdt();
+        require!(fee_token == self.fee_token().get(), "Wrong fee token");
+        require!(fee >= job.fee, "Fee below minimum");
+        let threshold = threshold.into_option().unwrap_or(1);
         require!(
-            !caller_requests.contains_key(&nonce),
-            "Existing account and nonce in requests"
+            threshold > 0
+                && threshold as usize <= self.job_nodes(&job_id).len()
+                && threshold as usize <= MAX_SUBMISSION_VALUES,
+            "Invalid threshold"
         );
 
+        let caller = self.blockchain().get_caller();
         let mut nonces = self.nonces();
-        let expected_nonce = nonces.get(&caller).map_or(0, |last_nonce| last_nonce + 1);
-        require!(nonce == expected_nonce, "Invalid nonce");
+        if let Some(last_nonce) = nonces.get(&caller) {
+            require!(nonce > last_nonce, "Invalid nonce");
+        }
+
+        let request_id = self.crypto().keccak256(&request_id_preimage(
+            &caller,
+            nonce,
+            self.blockchain().get_block_nonce(),
+        ));
+        require!(
+            !self.requests().contains_key(&request_id),
+            "Existing request id"
+        );
 
         // store request
         let new_request = OracleRequest {
             caller: caller.clone(),
+            nonce,
+            job_id,
             callback_address,
             callback_method,
+            callback_gas_limit,
             data,
+            fee,
+            threshold,
+            expiration: self.blockchain().get_block_timestamp() + EXPIRY_TIME,
         };
-        caller_requests.insert(nonce, new_request);
+        self.requests().insert(request_id.clone(), new_request);
         nonces.insert(caller, nonce);
+
+        request_id
     }
 
     #[endpoint(fulfillRequest)]
-    fn fulfill_request(&self, address: ManagedAddress, nonce: u64, data: ManagedBuffer) {
-        self.only_authorized_node();
+    fn fulfill_request(&self, request_id: RequestId<Self::Api>, data: ManagedBuffer) {
+        self.require_not_paused();
 
-        // Get the request
-        let requests = self.requests();
-        let address_requests_option = requests.get(&address);
+        let request = self
+            .requests()
+            .get(&request_id)
+            .unwrap_or_else(|| sc_panic!("Did not find the request to fulfill."));
+        self.only_authorized_node(&request.job_id);
 
+        let node = self.blockchain().get_caller();
+        let mut answers_mapper = self.request_answers(&request_id);
         require!(
-            address_requests_option.is_some(),
-            "Did not find the account to fulfill."
+            !answers_mapper.contains_key(&node),
+            "Node already answered this request"
         );
-        let mut address_requests = address_requests_option.unwrap();
+        answers_mapper.insert(node.clone(), data);
+        if answers_mapper.len() < request.threshold as usize {
+            return;
+        }
+
+        // the fee is split between the answering nodes,
+        // the node completing the threshold also gets the remainder of the split
+        let fee_share = &request.fee / &BigUint::from(request.threshold);
+        let mut remainder = request.fee.clone();
+        let mut answers = ManagedVec::new();
+        for (answering_node, answer) in answers_mapper.iter() {
+            answers.push(answer);
+            if answering_node != node {
+                remainder -= &fee_share;
+                self.withdrawable_fees(&answering_node)
+                    .update(|fees| *fees += &fee_share);
+            }
+        }
+        self.withdrawable_fees(&node)
+            .update(|fees| *fees += remainder);
+        answers_mapper.clear();
+        self.requests().remove(&request_id);
+
+        let answer_type = self.get_job(&request.job_id).answer_type;
+        let data = answer_aggregation::aggregate(answer_type, &answers)
+            .unwrap_or_else(|error| sc_panic!(error.as_bytes()));
+
+        // asynchronous calls can't be given a gas limit, so the node has to provide enough gas
+        require!(
+            self.blockchain().get_gas_left() >= request.callback_gas_limit + DELIVERY_GAS_OVERHEAD,
+            "Not enough gas to deliver the answer"
+        );
+        let mut callback =
+            ContractCall::<Self::Api, ()>::new(request.callback_address, request.callback_method);
+        callback.push_endpoint_arg(&request_id);
+        callback.push_endpoint_arg(&request.nonce);
+        callback.push_endpoint_arg(&data);
+        callback
+            .async_call()
+            .with_callback(self.callbacks().delivery_callback(
+                request.caller,
+                request.nonce,
+                request_id,
+                data,
+            ))
+            .call_and_exit();
+    }
+
+    #[callback]
+    fn delivery_callback(
+        &self,
+        #[call_result] result: ManagedAsyncCallResult<IgnoreValue>,
+        caller: ManagedAddress,
+        nonce: u64,
+        request_id: RequestId<Self::Api>,
+        answer: ManagedBuffer,
+    ) {
+        match result {
+            ManagedAsyncCallResult::Ok(_) => {
+                self.delivery_status(&request_id)
+                    .set(DeliveryStatus::Delivered);
+                self.answer_delivered_event(&request_id, &caller, nonce);
+            }
+            ManagedAsyncCallResult::Err(error) => {
+                self.delivery_status(&request_id)
+                    .set(DeliveryStatus::Failed);
+                self.answer_delivery_failed_event(&request_id, &caller, nonce, &error.err_msg);
+                self.failed_deliveries(&caller)
+                    .insert(request_id, FailedDelivery { nonce, answer });
+            }
+        }
+    }
 
-        let request_option = address_requests.get(&nonce);
+    /// Returns the answer of a request of the caller whose callback failed,
+    /// and removes it from the retry queue.
+    #[endpoint(claimAnswer)]
+    fn claim_answer(&self, request_id: RequestId<Self::Api>) -> ManagedBuffer {
+        let caller = self.blockchain().get_caller();
+        let failed_delivery = self
+            .failed_deliveries(&caller)
+            .remove(&request_id)
+            .unwrap_or_else(|| sc_panic!("No failed delivery for this request"));
+        self.delivery_status(&request_id)
+            .set(DeliveryStatus::Claimed);
+
+        failed_delivery.answer
+    }
+
+    /// Removes an expired request of the caller and refunds its fee.
+    #[endpoint(cancelRequest)]
+    fn cancel_request(&self, request_id: RequestId<Self::Api>) {
+        let caller = self.blockchain().get_caller();
+        let request = self
+            .requests()
+            .get(&request_id)
+            .unwrap_or_else(|| sc_panic!("Did not find the request to cancel."));
         require!(
-            request_option.is_some(),
-            "Did not find the request (nonce) to fulfill."
+            request.caller == caller,
+            "Only the caller can cancel a request"
+        );
+        require!(
+            request.is_expired(self.blockchain().get_block_timestamp()),
+            "Request is not expired"
         );
-        let request = request_option.unwrap();
 
-        address_requests.remove(&nonce);
+        self.requests().remove(&request_id);
+        self.request_answers(&request_id).clear();
+        if request.fee > 0u32 {
+            self.send()
+                .direct(&caller, &self.fee_token().get(), 0, &request.fee);
+        }
+    }
 
-        let mut client = self.client_proxy(request.callback_address);
-        client.reply(nonce, data).async_call().call_and_exit();
+    /// Sends the fees earned by the caller; everything, if no amount is given.
+    #[endpoint(withdraw)]
+    fn withdraw(&self, amount: OptionalValue<BigUint>) {
+        let caller = self.blockchain().get_caller();
+        let fees_mapper = self.withdrawable_fees(&caller);
+        let available = fees_mapper.get();
+        let amount = amount.into_option().unwrap_or_else(|| available.clone());
+        require!(amount > 0u32, "Nothing to withdraw");
+        require!(amount <= available, "Insufficient withdrawable fees");
+
+        fees_mapper.set(&(available - &amount));
+        self.send()
+            .direct(&caller, &self.fee_token().get(), 0, &amount);
+    }
+
+    /// Registers a job, or replaces the spec and the nodes of an existing one.
+    /// The nodes must already be authorized.
+    #[only_owner]
+    #[endpoint(registerJob)]
+    fn register_job(
+        &self,
+        job_id: ManagedBuffer,
+        description: ManagedBuffer,
+        fee: BigUint,
+        answer_type: AnswerType,
+        nodes: MultiValueEncoded<ManagedAddress>,
+    ) {
+        require!(!job_id.is_empty(), "Empty job id");
+        self.jobs().insert(
+            job_id.clone(),
+            JobSpec {
+                description,
+                fee,
+                answer_type,
+            },
+        );
+
+        let mut job_nodes = self.job_nodes(&job_id);
+        job_nodes.clear();
+        for node in nodes {
+            require!(
+                self.authorized_nodes().contains(&node),
+                "Job node is not authorized"
+            );
+            job_nodes.insert(node);
+        }
     }
 
+    /// Requests of a removed job can no longer be fulfilled, only cancelled once expired.
     #[only_owner]
+    #[endpoint(removeJob)]
+    fn remove_job(&self, job_id: ManagedBuffer) {
+        require!(self.jobs().remove(&job_id).is_some(), "Job not found");
+        self.job_nodes(&job_id).clear();
+    }
+
+    #[view(getJob)]
+    fn get_job(&self, job_id: &ManagedBuffer) -> JobSpec<Self::Api> {
+        self.jobs()
+            .get(job_id)
+            .unwrap_or_else(|| sc_panic!("Job not found"))
+    }
+
+    #[view(getJobIds)]
+    fn get_job_ids(&self) -> MultiValueEncoded<ManagedBuffer> {
+        let mut job_ids = MultiValueEncoded::new();
+        for job_id in self.jobs().keys() {
+            job_ids.push(job_id);
+        }
+
+        job_ids
+    }
+
+    /// Relays the values of a node for a round of an aggregator, which can be in another shard.
+    /// The outcome is recorded once the aggregator's answer comes back.
     #[endpoint(submit)]
-    fn submit(&self, aggregator: ManagedAddress, round_id: u64, submission: BigUint) {
-        let mut submission_values = MultiValueEncoded::new();
-        submission_values.push(submission);
+    fn submit(
+        &self,
+        aggregator: ManagedAddress,
+        round_id: u64,
+        submission_values: MultiValueEncoded<BigUint>,
+    ) {
+        self.require_not_paused();
+        self.only_owner_or_authorized_node();
 
-        self.aggregator_proxy(aggregator)
+        self.aggregator_proxy(aggregator.clone())
             .submit(round_id, submission_values)
             .async_call()
+            .with_callback(self.callbacks().relay_callback(
+                self.blockchain().get_caller(),
+                aggregator,
+                round_id,
+            ))
             .call_and_exit();
     }
 
+    #[callback]
+    fn relay_callback(
+        &self,
+        #[call_result] result: ManagedAsyncCallResult<IgnoreValue>,
+        node: ManagedAddress,
+        aggregator: ManagedAddress,
+        round_id: u64,
+    ) {
+        let outcome = match result {
+            ManagedAsyncCallResult::Ok(_) => RelayOutcome::Accepted,
+            ManagedAsyncCallResult::Err(error) => RelayOutcome::Rejected(error.err_msg),
+        };
+        self.record_relay_result(node, &aggregator, Some(round_id), 1, outcome);
+    }
+
+    /// Relays submissions to several aggregators and price-aggregators in a single transaction.
+    /// The targets are called synchronously, so they must be in the same shard as the oracle.
+    /// The batch is all-or-nothing: if any target rejects its submission, the whole
+    /// transaction fails and nothing is recorded, so only accepted outcomes are ever
+    /// recorded here; use `submit` for an outcome recorded either way.
+    /// The oracle has to be one of the oracles of the price-aggregators it submits to.
+    #[endpoint(submitBatch)]
+    fn submit_batch(&self, submissions: MultiValueEncoded<RelaySubmission<Self::Api>>) {
+        self.require_not_paused();
+        self.only_owner_or_authorized_node();
+        let node = self.blockchain().get_caller();
+
+        let mut price_batch: Option<(
+            ManagedAddress,
+            MultiValueEncoded<PriceSubmission<Self::Api>>,
+        )> = None;
+        for submission in submissions {
+            match submission {
+                RelaySubmission::Aggregator {
+                    aggregator,
+                    round_id,
+                    values,
+                } => {
+                    if let Some((price_aggregator, batch)) = price_batch.take() {
+                        self.relay_price_batch(&node, price_aggregator, batch);
+                    }
+                    self.aggregator_proxy(aggregator.clone())
+                        .submit(round_id, MultiValueEncoded::from(values))
+                        .execute_on_dest_context::<()>();
+                    self.record_relay_result(
+                        node.clone(),
+                        &aggregator,
+                        Some(round_id),
+                        1,
+                        RelayOutcome::Accepted,
+                    );
+                }
+                RelaySubmission::PriceAggregator {
+                    price_aggregator,
+                    from,
+                    to,
+                    timestamp,
+                    price,
+                } => {
+                    let same_target =
+                        matches!(&price_batch, Some((target, _)) if *target == price_aggregator);
+                    if !same_target {
+                        if let Some((previous, batch)) = price_batch.take() {
+                            self.relay_price_batch(&node, previous, batch);
+                        }
+                        price_batch = Some((price_aggregator, MultiValueEncoded::new()));
+                    }
+                    if let Some((_, batch)) = price_batch.as_mut() {
+                        batch.push((from, to, timestamp, price).into());
+                    }
+                }
+            }
+        }
+        if let Some((price_aggregator, batch)) = price_batch {
+            self.relay_price_batch(&node, price_aggregator, batch);
+        }
+    }
+
+    fn relay_price_batch(
+        &self,
+        node: &ManagedAddress,
+        price_aggregator: ManagedAddress,
+        batch: MultiValueEncoded<PriceSubmission<Self::Api>>,
+    ) {
+        let submissions_count = batch.len() as u32;
+        self.price_aggregator_proxy(price_aggregator.clone())
+            .submit_batch(batch)
+            .execute_on_dest_context::<()>();
+        self.record_relay_result(
+            node.clone(),
+            &price_aggregator,
+            None,
+            submissions_count,
+            RelayOutcome::Accepted,
+        );
+    }
+
+    fn record_relay_result(
+        &self,
+        node: ManagedAddress,
+        target: &ManagedAddress,
+        round_id: Option<u64>,
+        submissions_count: u32,
+        outcome: RelayOutcome<Self::Api>,
+    ) {
+        match &outcome {
+            RelayOutcome::Accepted => {
+                self.submission_relayed_event(target, &node, round_id, submissions_count)
+            }
+            RelayOutcome::Rejected(err_msg) => {
+                self.submission_relay_failed_event(target, &node, round_id, err_msg)
+            }
+        }
+
+        let result = RelayResult {
+            node,
+            timestamp: self.blockchain().get_block_timestamp(),
+            round_id,
+            submissions_count,
+            outcome,
+        };
+        let mut results = self.relay_results(target);
+        let relay_count = self.relay_count(target).get();
+        if results.len() < RELAY_HISTORY_LEN {
+            results.push(&result);
+        } else {
+            results.set(relay_count as usize % RELAY_HISTORY_LEN + 1, &result);
+        }
+        self.relay_count(target).set(relay_count + 1);
+    }
+
+    /// The latest submissions relayed to a target, oldest first
+    #[view(relayResults)]
+    fn get_relay_results(
+        &self,
+        target: ManagedAddress,
+    ) -> MultiValueEncoded<RelayResult<Self::Api>> {
+        let results = self.relay_results(&target);
+        let mut history = MultiValueEncoded::new();
+        if results.is_empty() {
+            return history;
+        }
+        let oldest = self.relay_count(&target).get() as usize % results.len();
+        for offset in 0..results.len() {
+            history.push(results.get((oldest + offset) % results.len() + 1));
+        }
+
+        history
+    }
+
+    #[view(lastRelayResult)]
+    fn last_relay_result(&self, target: ManagedAddress) -> OptionalValue<RelayResult<Self::Api>> {
+        let results = self.relay_results(&target);
+        if results.is_empty() {
+            return OptionalValue::None;
+        }
+        let last = (self.relay_count(&target).get() as usize - 1) % results.len();
+        OptionalValue::Some(results.get(last + 1))
+    }
+
     #[only_owner]
     #[endpoint(addAuthorization)]
     fn add_authorization(&self, node: ManagedAddress) {
@@ -138,17 +615,98 @@ pub trait Oracle {
         );
     }
 
-    fn only_authorized_node(&self) {
+    #[only_owner]
+    #[endpoint(setGuardian)]
+    fn set_guardian(&self, guardian: ManagedAddress) {
+        self.guardian().set(&guardian);
+    }
+
+    /// Lets the guardian stop new requests, fulfilments and submissions.
+    /// Only the owner can unpause the contract.
+    #[endpoint(emergencyPause)]
+    fn emergency_pause(&self) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.blockchain().get_owner_address()
+                || (!self.guardian().is_empty() && caller == self.guardian().get()),
+            "only callable by guardian"
+        );
+        self.set_paused(true);
+    }
+
+    fn require_not_paused(&self) {
+        require!(self.not_paused(), PAUSED_ERROR_MSG);
+    }
+
+    fn require_valid_request_data(&self, data: &ManagedBuffer) {
+        require!(data.len() <= MAX_REQUEST_DATA_LEN, "Request data too long");
+        let mut buffer = [0u8; MAX_REQUEST_DATA_LEN];
+        let bytes = data.load_to_byte_array(&mut buffer);
+        require!(
+            request_cbor::validate_parameters(bytes).is_ok(),
+            "Invalid request data"
+        );
+    }
+
+    fn only_authorized_node(&self, job_id: &ManagedBuffer) {
+        let caller = self.blockchain().get_caller();
         require!(
-            self.authorized_nodes()
-                .contains(&self.blockchain().get_caller()),
+            self.authorized_nodes().contains(&caller) && self.job_nodes(job_id).contains(&caller),
             "Not an authorized node to fulfill requests."
         );
     }
 
-    #[proxy]
-    fn client_proxy(&self, to: ManagedAddress) -> client_proxy::Proxy<Self::Api>;
+    fn only_owner_or_authorized_node(&self) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            caller == self.blockchain().get_owner_address()
+                || self.authorized_nodes().contains(&caller),
+            "Only the owner or an authorized node can submit"
+        );
+    }
+
+    #[event("answer_delivered")]
+    fn answer_delivered_event(
+        &self,
+        #[indexed] request_id: &RequestId<Self::Api>,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] nonce: u64,
+    );
+
+    #[event("answer_delivery_failed")]
+    fn answer_delivery_failed_event(
+        &self,
+        #[indexed] request_id: &RequestId<Self::Api>,
+        #[indexed] caller: &ManagedAddress,
+        #[indexed] nonce: u64,
+        err_msg: &ManagedBuffer,
+    );
+
+    /// `round_id` is only set for submissions to an aggregator
+    #[event("submission_relayed")]
+    fn submission_relayed_event(
+        &self,
+        #[indexed] target: &ManagedAddress,
+        #[indexed] node: &ManagedAddress,
+        #[indexed] round_id: Option<u64>,
+        submissions_count: u32,
+    );
+
+    #[event("submission_relay_failed")]
+    fn submission_relay_failed_event(
+        &self,
+        #[indexed] target: &ManagedAddress,
+        #[indexed] node: &ManagedAddress,
+        #[indexed] round_id: Option<u64>,
+        err_msg: &ManagedBuffer,
+    );
 
     #[proxy]
     fn aggregator_proxy(&self, to: ManagedAddress) -> aggregator::Proxy<Self::Api>;
+
+    #[proxy]
+    fn price_aggregator_proxy(
+        &self,
+        to: ManagedAddress,
+    ) -> price_aggregator_proxy::price_aggregator_proxy::Proxy<Self::Api>;
 }
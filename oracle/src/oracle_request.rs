@@ -1,21 +1,67 @@
 use elrond_wasm::{
     api::ManagedTypeApi,
-    types::{ManagedAddress, ManagedBuffer},
+    types::{BigUint, ManagedAddress, ManagedBuffer, ManagedByteArray},
 };
 
 elrond_wasm::derive_imports!();
 
+/// Identifies a request across contracts: the keccak256 hash of `request_id_preimage`
+pub type RequestId<M> = ManagedByteArray<M, 32>;
+
+/// The caller address followed by the big endian nonce and block nonce of the request
+pub fn request_id_preimage<M: ManagedTypeApi>(
+    caller: &ManagedAddress<M>,
+    nonce: u64,
+    block_nonce: u64,
+) -> ManagedBuffer<M> {
+    let mut preimage = caller.as_managed_buffer().clone();
+    preimage.append_bytes(&nonce.to_be_bytes()[..]);
+    preimage.append_bytes(&block_nonce.to_be_bytes()[..]);
+    preimage
+}
+
 #[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
 pub struct OracleRequest<M: ManagedTypeApi> {
     pub caller: ManagedAddress<M>,
+    pub nonce: u64,
+    pub job_id: ManagedBuffer<M>,
     pub callback_address: ManagedAddress<M>,
     pub callback_method: ManagedBuffer<M>,
+    pub callback_gas_limit: u64,
     pub data: ManagedBuffer<M>,
+    pub fee: BigUint<M>,
+    /// How many nodes have to answer before the request is fulfilled
+    pub threshold: u32,
+    pub expiration: u64,
+}
+
+impl<M: ManagedTypeApi> OracleRequest<M> {
+    pub fn is_expired(&self, current_timestamp: u64) -> bool {
+        self.expiration <= current_timestamp
+    }
+}
+
+/// What happened to the answer of a fulfilled request
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Clone, Copy)]
+pub enum DeliveryStatus {
+    Unknown,
+    Delivered,
+    Failed,
+    Claimed,
+}
+
+/// An answer whose callback failed, kept until the requester claims it
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct FailedDelivery<M: ManagedTypeApi> {
+    pub nonce: u64,
+    pub answer: ManagedBuffer<M>,
 }
 
 #[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
 pub struct RequestView<M: ManagedTypeApi> {
     pub address: ManagedAddress<M>,
     pub nonce: u64,
+    pub job_id: ManagedBuffer<M>,
     pub data: ManagedBuffer<M>,
+    pub threshold: u32,
 }
@@ -0,0 +1,45 @@
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+/// A submission relayed by the oracle on behalf of one of its nodes
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub enum RelaySubmission<M: ManagedTypeApi> {
+    /// Values for a round of an aggregator, in the order of its feed schema
+    Aggregator {
+        aggregator: ManagedAddress<M>,
+        round_id: u64,
+        values: ManagedVec<M, BigUint<M>>,
+    },
+    /// A price for a pair of a price-aggregator; consecutive submissions
+    /// for the same price-aggregator are sent with a single `submitBatch`
+    PriceAggregator {
+        price_aggregator: ManagedAddress<M>,
+        from: ManagedBuffer<M>,
+        to: ManagedBuffer<M>,
+        timestamp: u64,
+        price: BigUint<M>,
+    },
+}
+
+/// The number of relay results kept for each target
+pub const RELAY_HISTORY_LEN: usize = 10;
+
+/// What the target did with a relayed submission
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq)]
+pub enum RelayOutcome<M: ManagedTypeApi> {
+    Accepted,
+    /// Rejected by the target, with its error message
+    Rejected(ManagedBuffer<M>),
+}
+
+/// A submission relayed to a contract, and its outcome
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct RelayResult<M: ManagedTypeApi> {
+    pub node: ManagedAddress<M>,
+    pub timestamp: u64,
+    /// The round of a submission to an aggregator, none for a price-aggregator batch
+    pub round_id: Option<u64>,
+    /// The number of prices in a price-aggregator batch, one for an aggregator submission
+    pub submissions_count: u32,
+    pub outcome: RelayOutcome<M>,
+}
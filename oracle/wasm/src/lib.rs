@@ -9,12 +9,34 @@ elrond_wasm_node::wasm_endpoints! {
     (
         addAuthorization
         authorizedNodes
+        callBack
+        cancelRequest
+        claimAnswer
+        deliveryStatus
+        emergencyPause
+        expiredRequests
+        failedDeliveries
         fulfillRequest
+        getFeeToken
+        getGuardian
+        getJob
+        getJobIds
+        isPaused
+        jobNodes
+        lastNonces
+        lastRelayResult
+        pause
+        registerJob
+        relayResults
         removeAuthorization
+        removeJob
         request
         requestsAsVec
+        setGuardian
         submit
+        submitBatch
+        unpause
+        withdraw
+        withdrawableFees
     )
 }
-
-elrond_wasm_node::wasm_empty_callback! {}
@@ -0,0 +1,155 @@
+#![no_std]
+
+elrond_wasm::imports!();
+elrond_wasm::derive_imports!();
+
+mod request_builder;
+pub use oracle::oracle_request::RequestId;
+pub use request_builder::*;
+
+/// A request sent to the oracle and not answered yet
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct PendingRequest<M: ManagedTypeApi> {
+    /// Only known once the oracle's reply to the request came back
+    pub request_id: Option<RequestId<M>>,
+}
+
+/// Lets a contract request data from an oracle and consume its answers.
+///
+/// Requests are built with an `OracleRequestBuilder` and sent with `send_oracle_request`.
+/// The oracle delivers each answer to the handler endpoint named in the request, with the
+/// arguments `(request_id, nonce, answer)`. The handler is the contract's hook: it passes
+/// the request to `consume_oracle_answer`, which checks that the answer comes from the oracle
+/// and belongs to a pending request, and then handles the answer itself.
+/// Answers whose delivery failed are pulled with `claim_oracle_answer`, whose callback passes
+/// the request to `consume_claimed_answer` before handling the answer the same way.
+#[elrond_wasm::module]
+pub trait OracleConsumerModule {
+    #[only_owner]
+    #[endpoint(setOracleAddress)]
+    fn set_oracle_address(&self, address: ManagedAddress) {
+        require!(
+            self.blockchain().is_smart_contract(&address),
+            "Invalid oracle address"
+        );
+
+        self.oracle_address().set(&address);
+    }
+
+    /// Sends a request to the oracle, paying `fee` as the request fee.
+    /// The request is pending under its nonce from now on, so that an answer delivered
+    /// before the oracle's reply to the request comes back is not lost.
+    fn send_oracle_request(
+        &self,
+        request: OracleRequestBuilder<Self::Api>,
+        fee_token: EgldOrEsdtTokenIdentifier,
+        fee: BigUint,
+    ) {
+        let nonce = self.oracle_nonce().get();
+        self.oracle_nonce().set(nonce + 1);
+        let threshold = match request.threshold {
+            Some(threshold) => OptionalValue::Some(threshold),
+            None => OptionalValue::None,
+        };
+        self.pending_requests()
+            .insert(nonce, PendingRequest { request_id: None });
+
+        self.oracle_proxy(self.oracle_address().get())
+            .request(
+                request.job_id,
+                self.blockchain().get_sc_address(),
+                request.handler,
+                request.callback_gas_limit,
+                nonce,
+                request.data,
+                threshold,
+            )
+            .with_egld_or_single_esdt_token_transfer(fee_token, 0, fee)
+            .async_call()
+            .with_callback(self.callbacks().oracle_request_callback(nonce))
+            .call_and_exit();
+    }
+
+    #[callback]
+    fn oracle_request_callback(
+        &self,
+        #[call_result] result: ManagedAsyncCallResult<RequestId<Self::Api>>,
+        nonce: u64,
+    ) {
+        match result {
+            ManagedAsyncCallResult::Ok(request_id) => {
+                // the answer may already have been delivered
+                if let Some(mut pending) = self.pending_requests().get(&nonce) {
+                    pending.request_id = Some(request_id);
+                    self.pending_requests().insert(nonce, pending);
+                }
+            }
+            ManagedAsyncCallResult::Err(error) => {
+                self.pending_requests().remove(&nonce);
+                self.oracle_request_failed_event(nonce, &error.err_msg);
+            }
+        }
+    }
+
+    /// Checks that an answer comes from the oracle and belongs to a pending request,
+    /// with the same id if it is already known. The request is no longer pending afterwards.
+    fn consume_oracle_answer(&self, request_id: &RequestId<Self::Api>, nonce: u64) {
+        require!(
+            self.blockchain().get_caller() == self.oracle_address().get(),
+            "Only oracle can reply"
+        );
+        let pending = self
+            .pending_requests()
+            .get(&nonce)
+            .unwrap_or_else(|| sc_panic!("Unknown request"));
+        if let Some(pending_request_id) = &pending.request_id {
+            require!(pending_request_id == request_id, "Unknown request");
+        }
+
+        self.pending_requests().remove(&nonce);
+    }
+
+    /// Pulls from the oracle the answer of a pending request whose delivery failed.
+    /// The answer is passed to `callback`, which should handle it if `consume_claimed_answer`
+    /// returns `true`, and emit `answer_claim_failed_event` if the claim failed.
+    fn claim_oracle_answer(&self, nonce: u64, callback: CallbackClosure<Self::Api>) {
+        let request_id = self
+            .pending_requests()
+            .get(&nonce)
+            .and_then(|pending| pending.request_id)
+            .unwrap_or_else(|| sc_panic!("Unknown request"));
+
+        self.oracle_proxy(self.oracle_address().get())
+            .claim_answer(request_id)
+            .async_call()
+            .with_callback(callback)
+            .call_and_exit();
+    }
+
+    /// Stops tracking a request whose answer was claimed; returns `false` if it was
+    /// answered in the meantime
+    fn consume_claimed_answer(&self, nonce: u64) -> bool {
+        self.pending_requests().remove(&nonce).is_some()
+    }
+
+    #[event("oracle_request_failed")]
+    fn oracle_request_failed_event(&self, #[indexed] nonce: u64, err_msg: &ManagedBuffer);
+
+    #[event("answer_claim_failed")]
+    fn answer_claim_failed_event(&self, #[indexed] nonce: u64, err_msg: &ManagedBuffer);
+
+    #[proxy]
+    fn oracle_proxy(&self, to: ManagedAddress) -> oracle::Proxy<Self::Api>;
+
+    #[view(getOracleAddress)]
+    #[storage_mapper("oracle_address")]
+    fn oracle_address(&self) -> SingleValueMapper<ManagedAddress>;
+
+    #[storage_mapper("nonce")]
+    fn oracle_nonce(&self) -> SingleValueMapper<u64>;
+
+    /// The requests not answered yet, by their nonce
+    #[view(pendingRequests)]
+    #[storage_mapper("pending_requests")]
+    fn pending_requests(&self) -> MapMapper<u64, PendingRequest<Self::Api>>;
+}
@@ -0,0 +1,146 @@
+elrond_wasm::imports!();
+
+use request_cbor::{Encoder, Sink, TAG_NEGATIVE_BIGNUM, TAG_POSITIVE_BIGNUM};
+
+pub const DEFAULT_CALLBACK_GAS_LIMIT: u64 = 10_000_000;
+
+struct ManagedBufferSink<'a, M: ManagedTypeApi>(&'a mut ManagedBuffer<M>);
+
+impl<'a, M: ManagedTypeApi> Sink for ManagedBufferSink<'a, M> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.append_bytes(bytes);
+    }
+}
+
+/// Builds an oracle request for a job, with structured parameters.
+///
+/// Like Chainlink's request buffers, `data` holds CBOR encoded key/value pairs,
+/// without a map header. Keys and string values must be valid UTF-8.
+/// Arrays and maps are started with a key, filled with the `push_*` methods,
+/// or with key/value pairs for maps, and closed with `end_sequence`.
+pub struct OracleRequestBuilder<M: ManagedTypeApi> {
+    pub job_id: ManagedBuffer<M>,
+    pub handler: ManagedBuffer<M>,
+    pub callback_gas_limit: u64,
+    pub threshold: Option<u32>,
+    pub data: ManagedBuffer<M>,
+}
+
+impl<M: ManagedTypeApi> OracleRequestBuilder<M> {
+    /// The answer will be delivered to the `handler` endpoint of the consumer
+    pub fn new(job_id: ManagedBuffer<M>, handler: &[u8]) -> Self {
+        OracleRequestBuilder {
+            job_id,
+            handler: ManagedBuffer::new_from_bytes(handler),
+            callback_gas_limit: DEFAULT_CALLBACK_GAS_LIMIT,
+            threshold: None,
+            data: ManagedBuffer::new(),
+        }
+    }
+
+    /// The minimum gas of the oracle's call delivering the answer
+    pub fn with_callback_gas_limit(mut self, callback_gas_limit: u64) -> Self {
+        self.callback_gas_limit = callback_gas_limit;
+        self
+    }
+
+    /// Requires the answers of `threshold` nodes of the job
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    pub fn add_string<K: Into<ManagedBuffer<M>>>(self, key: K, value: &ManagedBuffer<M>) -> Self {
+        self.push_key(key).push_string(value)
+    }
+
+    pub fn add_bytes<K: Into<ManagedBuffer<M>>>(self, key: K, value: &ManagedBuffer<M>) -> Self {
+        self.push_key(key).push_bytes(value)
+    }
+
+    pub fn add_unsigned<K: Into<ManagedBuffer<M>>>(self, key: K, value: &BigUint<M>) -> Self {
+        self.push_key(key).push_unsigned(value)
+    }
+
+    pub fn add_signed<K: Into<ManagedBuffer<M>>>(self, key: K, value: &BigInt<M>) -> Self {
+        self.push_key(key).push_signed(value)
+    }
+
+    pub fn add_bool<K: Into<ManagedBuffer<M>>>(self, key: K, value: bool) -> Self {
+        self.push_key(key).push_bool(value)
+    }
+
+    pub fn begin_array<K: Into<ManagedBuffer<M>>>(mut self, key: K) -> Self {
+        self = self.push_key(key);
+        self.encoder().begin_indefinite_array();
+        self
+    }
+
+    pub fn begin_map<K: Into<ManagedBuffer<M>>>(mut self, key: K) -> Self {
+        self = self.push_key(key);
+        self.encoder().begin_indefinite_map();
+        self
+    }
+
+    /// Closes the last array or map which was started
+    pub fn end_sequence(mut self) -> Self {
+        self.encoder().end_indefinite();
+        self
+    }
+
+    pub fn push_string(mut self, value: &ManagedBuffer<M>) -> Self {
+        self.encoder().write_text_header(value.len());
+        self.data.append(value);
+        self
+    }
+
+    pub fn push_bytes(mut self, value: &ManagedBuffer<M>) -> Self {
+        self.encoder().write_bytes_header(value.len());
+        self.data.append(value);
+        self
+    }
+
+    /// Values above 64 bits are written as bignums
+    pub fn push_unsigned(mut self, value: &BigUint<M>) -> Self {
+        match value.to_u64() {
+            Some(small_value) => self.encoder().write_uint(small_value),
+            None => self.push_bignum(TAG_POSITIVE_BIGNUM, value),
+        }
+        self
+    }
+
+    /// Values outside the 64 bits range are written as bignums
+    pub fn push_signed(mut self, value: &BigInt<M>) -> Self {
+        if let Some(small_value) = value.to_i64() {
+            self.encoder().write_int(small_value);
+        } else if value.sign() == Sign::Minus {
+            // negative bignums hold -1 - value
+            let magnitude = value.magnitude() - BigUint::from(1u32);
+            self.push_bignum(TAG_NEGATIVE_BIGNUM, &magnitude);
+        } else {
+            self.push_bignum(TAG_POSITIVE_BIGNUM, &value.magnitude());
+        }
+        self
+    }
+
+    pub fn push_bool(mut self, value: bool) -> Self {
+        self.encoder().write_bool(value);
+        self
+    }
+
+    fn push_key<K: Into<ManagedBuffer<M>>>(self, key: K) -> Self {
+        self.push_string(&key.into())
+    }
+
+    fn push_bignum(&mut self, tag: u64, value: &BigUint<M>) {
+        let bytes = value.to_bytes_be_buffer();
+        let mut encoder = self.encoder();
+        encoder.write_tag(tag);
+        encoder.write_bytes_header(bytes.len());
+        self.data.append(&bytes);
+    }
+
+    fn encoder(&mut self) -> Encoder<ManagedBufferSink<'_, M>> {
+        Encoder::new(ManagedBufferSink(&mut self.data))
+    }
+}
@@ -6,9 +6,12 @@ pub const EGLD_TICKER: &[u8] = b"EGLD";
 pub const DOLLAR_TICKER: &[u8] = b"USD";
 
 pub type AggregatorResultAsMultiResult<M> =
-    MultiValue5<u32, ManagedBuffer<M>, ManagedBuffer<M>, BigUint<M>, u8>;
+    MultiValue6<u32, ManagedBuffer<M>, ManagedBuffer<M>, u64, BigUint<M>, u8>;
 
-mod price_aggregator_proxy {
+/// A price submitted for a pair: from, to, timestamp and price
+pub type PriceSubmission<M> = MultiValue4<ManagedBuffer<M>, ManagedBuffer<M>, u64, BigUint<M>>;
+
+pub mod price_aggregator_proxy {
     elrond_wasm::imports!();
 
     #[elrond_wasm::proxy]
@@ -19,6 +22,9 @@ mod price_aggregator_proxy {
             from: ManagedBuffer,
             to: ManagedBuffer,
         ) -> OptionalValue<super::AggregatorResultAsMultiResult<Self::Api>>;
+
+        #[endpoint(submitBatch)]
+        fn submit_batch(&self, submissions: MultiValueEncoded<super::PriceSubmission<Self::Api>>);
     }
 }
 
@@ -26,18 +32,21 @@ pub struct AggregatorResult<M: ManagedTypeApi> {
     pub round_id: u32,
     pub from_token_name: ManagedBuffer<M>,
     pub to_token_name: ManagedBuffer<M>,
+    pub timestamp: u64,
     pub price: BigUint<M>,
     pub decimals: u8,
 }
 
 impl<M: ManagedTypeApi> From<AggregatorResultAsMultiResult<M>> for AggregatorResult<M> {
     fn from(multi_result: AggregatorResultAsMultiResult<M>) -> Self {
-        let (round_id, from_token_name, to_token_name, price, decimals) = multi_result.into_tuple();
+        let (round_id, from_token_name, to_token_name, timestamp, price, decimals) =
+            multi_result.into_tuple();
 
         AggregatorResult {
             round_id,
             from_token_name,
             to_token_name,
+            timestamp,
             price,
             decimals,
         }
@@ -0,0 +1,116 @@
+use crate::*;
+
+/// A CBOR data item, or the start or end of one, as read by the `Decoder`
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Token<'a> {
+    Unsigned(u64),
+    /// The negative integer `-1 - n`
+    Negative(u64),
+    Bytes(&'a [u8]),
+    Text(&'a str),
+    /// The start of an array, with its length, or `None` if it ends with a `Break`
+    Array(Option<u64>),
+    /// The start of a map, with its number of pairs, or `None` if it ends with a `Break`
+    Map(Option<u64>),
+    /// A tag, applying to the next item
+    Tag(u64),
+    Bool(bool),
+    Null,
+    Break,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DecodeError {
+    UnexpectedEnd,
+    /// An initial byte which is not part of the supported subset
+    UnsupportedItem(u8),
+    InvalidUtf8,
+}
+
+/// Reads CBOR tokens from a byte slice, one at a time, without allocating.
+/// Strings are returned as slices of the input.
+pub struct Decoder<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        Decoder { input, position: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    /// Returns the next token, or `None` at the end of the input
+    pub fn next_token(&mut self) -> Result<Option<Token<'a>>, DecodeError> {
+        if self.is_at_end() {
+            return Ok(None);
+        }
+        let initial_byte = self.read_bytes(1)?[0];
+        let major = initial_byte >> 5;
+        let additional = initial_byte & 0x1f;
+
+        if additional == INDEFINITE_LENGTH {
+            return match major {
+                MAJOR_ARRAY => Ok(Some(Token::Array(None))),
+                MAJOR_MAP => Ok(Some(Token::Map(None))),
+                MAJOR_SIMPLE => Ok(Some(Token::Break)),
+                _ => Err(DecodeError::UnsupportedItem(initial_byte)),
+            };
+        }
+        if major == MAJOR_SIMPLE {
+            return match additional {
+                SIMPLE_FALSE => Ok(Some(Token::Bool(false))),
+                SIMPLE_TRUE => Ok(Some(Token::Bool(true))),
+                SIMPLE_NULL => Ok(Some(Token::Null)),
+                _ => Err(DecodeError::UnsupportedItem(initial_byte)),
+            };
+        }
+
+        let argument = self.read_argument(initial_byte, additional)?;
+        let token = match major {
+            MAJOR_UNSIGNED => Token::Unsigned(argument),
+            MAJOR_NEGATIVE => Token::Negative(argument),
+            MAJOR_BYTES => Token::Bytes(self.read_bytes(argument)?),
+            MAJOR_TEXT => {
+                let bytes = self.read_bytes(argument)?;
+                Token::Text(core::str::from_utf8(bytes).map_err(|_| DecodeError::InvalidUtf8)?)
+            }
+            MAJOR_ARRAY => Token::Array(Some(argument)),
+            MAJOR_MAP => Token::Map(Some(argument)),
+            _ => Token::Tag(argument),
+        };
+        Ok(Some(token))
+    }
+
+    fn read_argument(&mut self, initial_byte: u8, additional: u8) -> Result<u64, DecodeError> {
+        let len = match additional {
+            0..=23 => return Ok(additional as u64),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err(DecodeError::UnsupportedItem(initial_byte)),
+        };
+        let bytes = self.read_bytes(len)?;
+        Ok(bytes
+            .iter()
+            .fold(0u64, |value, byte| (value << 8) | *byte as u64))
+    }
+
+    fn read_bytes(&mut self, len: u64) -> Result<&'a [u8], DecodeError> {
+        let remaining = (self.input.len() - self.position) as u64;
+        if len > remaining {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let start = self.position;
+        self.position += len as usize;
+        Ok(&self.input[start..self.position])
+    }
+}
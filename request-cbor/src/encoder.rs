@@ -0,0 +1,158 @@
+use crate::*;
+
+/// Where the encoded bytes are written
+pub trait Sink {
+    fn write(&mut self, bytes: &[u8]);
+}
+
+impl<S: Sink + ?Sized> Sink for &mut S {
+    fn write(&mut self, bytes: &[u8]) {
+        (**self).write(bytes)
+    }
+}
+
+/// Writes CBOR items to a sink, always in their shortest form.
+///
+/// The `*_header` methods only write the head of an item, for callers
+/// which write the contents of strings and big integers to the sink themselves.
+pub struct Encoder<S: Sink> {
+    sink: S,
+}
+
+impl<S: Sink> Encoder<S> {
+    pub fn new(sink: S) -> Self {
+        Encoder { sink }
+    }
+
+    pub fn into_sink(self) -> S {
+        self.sink
+    }
+
+    pub fn write_uint(&mut self, value: u64) {
+        self.write_head(MAJOR_UNSIGNED, value);
+    }
+
+    pub fn write_int(&mut self, value: i64) {
+        if value < 0 {
+            // -1 - value, without overflowing for i64::MIN
+            self.write_head(MAJOR_NEGATIVE, !(value as u64));
+        } else {
+            self.write_head(MAJOR_UNSIGNED, value as u64);
+        }
+    }
+
+    /// Writes a non-negative integer given by its big endian bytes,
+    /// as a plain integer if it fits in 64 bits, or as a tag 2 bignum otherwise.
+    pub fn write_biguint(&mut self, be_bytes: &[u8]) {
+        let be_bytes = trim_leading_zeros(be_bytes);
+        if be_bytes.len() <= 8 {
+            self.write_uint(u64_from_be(be_bytes));
+        } else {
+            self.write_head(MAJOR_TAG, TAG_POSITIVE_BIGNUM);
+            self.write_bytes(be_bytes);
+        }
+    }
+
+    /// Writes the negative integer `-1 - n`, given the big endian bytes of `n`,
+    /// as a plain integer if `n` fits in 64 bits, or as a tag 3 bignum otherwise.
+    pub fn write_negative_biguint(&mut self, n_be_bytes: &[u8]) {
+        let n_be_bytes = trim_leading_zeros(n_be_bytes);
+        if n_be_bytes.len() <= 8 {
+            self.write_head(MAJOR_NEGATIVE, u64_from_be(n_be_bytes));
+        } else {
+            self.write_head(MAJOR_TAG, TAG_NEGATIVE_BIGNUM);
+            self.write_bytes(n_be_bytes);
+        }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_bytes_header(bytes.len());
+        self.sink.write(bytes);
+    }
+
+    pub fn write_str(&mut self, text: &str) {
+        self.write_text_header(text.len());
+        self.sink.write(text.as_bytes());
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_simple(if value { SIMPLE_TRUE } else { SIMPLE_FALSE });
+    }
+
+    pub fn write_null(&mut self) {
+        self.write_simple(SIMPLE_NULL);
+    }
+
+    pub fn write_tag(&mut self, tag: u64) {
+        self.write_head(MAJOR_TAG, tag);
+    }
+
+    pub fn write_bytes_header(&mut self, len: usize) {
+        self.write_head(MAJOR_BYTES, len as u64);
+    }
+
+    /// The caller must write `len` bytes of valid UTF-8 after it
+    pub fn write_text_header(&mut self, len: usize) {
+        self.write_head(MAJOR_TEXT, len as u64);
+    }
+
+    /// Starts an array of `len` items
+    pub fn begin_array(&mut self, len: usize) {
+        self.write_head(MAJOR_ARRAY, len as u64);
+    }
+
+    /// Starts a map of `len` key/value pairs
+    pub fn begin_map(&mut self, len: usize) {
+        self.write_head(MAJOR_MAP, len as u64);
+    }
+
+    /// Starts an array whose items are followed by `end_indefinite`
+    pub fn begin_indefinite_array(&mut self) {
+        self.sink.write(&[(MAJOR_ARRAY << 5) | INDEFINITE_LENGTH]);
+    }
+
+    /// Starts a map whose key/value pairs are followed by `end_indefinite`
+    pub fn begin_indefinite_map(&mut self) {
+        self.sink.write(&[(MAJOR_MAP << 5) | INDEFINITE_LENGTH]);
+    }
+
+    pub fn end_indefinite(&mut self) {
+        self.sink.write(&[BREAK]);
+    }
+
+    fn write_simple(&mut self, value: u8) {
+        self.sink.write(&[(MAJOR_SIMPLE << 5) | value]);
+    }
+
+    fn write_head(&mut self, major: u8, value: u64) {
+        let major = major << 5;
+        if value < 24 {
+            self.sink.write(&[major | value as u8]);
+        } else if value <= u8::MAX as u64 {
+            self.sink.write(&[major | 24, value as u8]);
+        } else if value <= u16::MAX as u64 {
+            self.sink.write(&[major | 25]);
+            self.sink.write(&(value as u16).to_be_bytes());
+        } else if value <= u32::MAX as u64 {
+            self.sink.write(&[major | 26]);
+            self.sink.write(&(value as u32).to_be_bytes());
+        } else {
+            self.sink.write(&[major | 27]);
+            self.sink.write(&value.to_be_bytes());
+        }
+    }
+}
+
+fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let first_non_zero = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    &bytes[first_non_zero..]
+}
+
+fn u64_from_be(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0u64, |value, byte| (value << 8) | *byte as u64)
+}
@@ -0,0 +1,40 @@
+//! Encoding of structured oracle request parameters, in a subset of CBOR (RFC 8949).
+//!
+//! Supported items: unsigned and negative integers, big integers (tags 2 and 3),
+//! byte and text strings, arrays and maps of definite or indefinite length,
+//! booleans and null. Floats and other simple values are not supported.
+//!
+//! Like Chainlink's request buffers, request parameters are a sequence of
+//! key/value pairs without a map header: off-chain decoders read them as an
+//! indefinite length map by wrapping them in `0xbf` ... `0xff`.
+//! [`validate_parameters`] checks that a buffer has this shape.
+
+#![no_std]
+
+mod decoder;
+mod encoder;
+mod validator;
+
+pub use decoder::{DecodeError, Decoder, Token};
+pub use encoder::{Encoder, Sink};
+pub use validator::{validate_parameters, ValidationError, MAX_NESTING_DEPTH};
+
+pub const MAJOR_UNSIGNED: u8 = 0;
+pub const MAJOR_NEGATIVE: u8 = 1;
+pub const MAJOR_BYTES: u8 = 2;
+pub const MAJOR_TEXT: u8 = 3;
+pub const MAJOR_ARRAY: u8 = 4;
+pub const MAJOR_MAP: u8 = 5;
+pub const MAJOR_TAG: u8 = 6;
+pub const MAJOR_SIMPLE: u8 = 7;
+
+pub const TAG_POSITIVE_BIGNUM: u64 = 2;
+pub const TAG_NEGATIVE_BIGNUM: u64 = 3;
+
+pub const SIMPLE_FALSE: u8 = 20;
+pub const SIMPLE_TRUE: u8 = 21;
+pub const SIMPLE_NULL: u8 = 22;
+
+/// Additional information value for indefinite lengths, and for the break stop code
+pub const INDEFINITE_LENGTH: u8 = 31;
+pub const BREAK: u8 = (MAJOR_SIMPLE << 5) | INDEFINITE_LENGTH;
@@ -0,0 +1,116 @@
+use crate::{DecodeError, Decoder, Token, TAG_NEGATIVE_BIGNUM, TAG_POSITIVE_BIGNUM};
+
+/// Arrays and maps can be nested at most this deep inside a parameter value
+pub const MAX_NESTING_DEPTH: usize = 16;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ValidationError {
+    Decode(DecodeError),
+    /// A parameter key which is not a text string
+    InvalidKey,
+    /// A key without a value, or an array or map cut short
+    MissingValue,
+    /// A break stop code outside of an indefinite length array or map
+    UnexpectedBreak,
+    /// A tag other than a bignum, or a bignum whose content is not a byte string
+    InvalidTag,
+    TooDeep,
+}
+
+impl From<DecodeError> for ValidationError {
+    fn from(error: DecodeError) -> Self {
+        ValidationError::Decode(error)
+    }
+}
+
+/// Checks that the input is a sequence of well-formed key/value pairs, with text keys,
+/// as written by the request builders.
+pub fn validate_parameters(input: &[u8]) -> Result<(), ValidationError> {
+    let mut decoder = Decoder::new(input);
+    while let Some(key) = decoder.next_token()? {
+        if !matches!(key, Token::Text(_)) {
+            return Err(ValidationError::InvalidKey);
+        }
+        let value = next_item(&mut decoder)?;
+        validate_item(&mut decoder, value, 0)?;
+    }
+    Ok(())
+}
+
+fn next_item<'a>(decoder: &mut Decoder<'a>) -> Result<Token<'a>, ValidationError> {
+    match decoder.next_token()? {
+        Some(Token::Break) => Err(ValidationError::UnexpectedBreak),
+        Some(token) => Ok(token),
+        None => Err(ValidationError::MissingValue),
+    }
+}
+
+/// Returns the next item of an indefinite length array or map, or `None` at its break
+fn next_item_or_break<'a>(decoder: &mut Decoder<'a>) -> Result<Option<Token<'a>>, ValidationError> {
+    match decoder.next_token()? {
+        Some(Token::Break) => Ok(None),
+        Some(token) => Ok(Some(token)),
+        None => Err(ValidationError::MissingValue),
+    }
+}
+
+fn validate_item(decoder: &mut Decoder, token: Token, depth: usize) -> Result<(), ValidationError> {
+    match token {
+        Token::Tag(TAG_POSITIVE_BIGNUM) | Token::Tag(TAG_NEGATIVE_BIGNUM) => {
+            match next_item(decoder)? {
+                Token::Bytes(_) => Ok(()),
+                _ => Err(ValidationError::InvalidTag),
+            }
+        }
+        Token::Tag(_) => Err(ValidationError::InvalidTag),
+        Token::Array(len) => {
+            if depth >= MAX_NESTING_DEPTH {
+                return Err(ValidationError::TooDeep);
+            }
+            match len {
+                Some(len) => {
+                    for _ in 0..len {
+                        let item = next_item(decoder)?;
+                        validate_item(decoder, item, depth + 1)?;
+                    }
+                }
+                None => {
+                    while let Some(item) = next_item_or_break(decoder)? {
+                        validate_item(decoder, item, depth + 1)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        Token::Map(len) => {
+            if depth >= MAX_NESTING_DEPTH {
+                return Err(ValidationError::TooDeep);
+            }
+            match len {
+                Some(len) => {
+                    for _ in 0..len {
+                        let key = next_item(decoder)?;
+                        validate_item(decoder, key, depth + 1)?;
+                        let value = next_item(decoder)?;
+                        validate_item(decoder, value, depth + 1)?;
+                    }
+                }
+                None => {
+                    while let Some(key) = next_item_or_break(decoder)? {
+                        validate_item(decoder, key, depth + 1)?;
+                        let value = next_item(decoder)?;
+                        validate_item(decoder, value, depth + 1)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        Token::Break => Err(ValidationError::UnexpectedBreak),
+        Token::Unsigned(_)
+        | Token::Negative(_)
+        | Token::Bytes(_)
+        | Token::Text(_)
+        | Token::Bool(_)
+        | Token::Null => Ok(()),
+    }
+}
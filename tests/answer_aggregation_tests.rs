@@ -0,0 +1,79 @@
+use elrond_wasm::types::{BigUint, ManagedBuffer, ManagedVec};
+use elrond_wasm_debug::DebugApi;
+use oracle::answer_aggregation;
+use oracle::job::AnswerType;
+
+fn to_answers(answers: Vec<&[u8]>) -> ManagedVec<DebugApi, ManagedBuffer<DebugApi>> {
+    let mut result = ManagedVec::new();
+    for answer in answers {
+        result.push(ManagedBuffer::new_from_bytes(answer));
+    }
+    result
+}
+
+fn to_numeric_answers(answers: Vec<u64>) -> ManagedVec<DebugApi, ManagedBuffer<DebugApi>> {
+    let mut result = ManagedVec::new();
+    for answer in answers {
+        result.push(BigUint::<DebugApi>::from(answer).to_bytes_be_buffer());
+    }
+    result
+}
+
+fn check_aggregate(answer_type: AnswerType, expected: &[u8], answers: Vec<&[u8]>) {
+    let actual = answer_aggregation::aggregate(answer_type, &to_answers(answers));
+    assert_eq!(Result::Ok(ManagedBuffer::new_from_bytes(expected)), actual);
+}
+
+fn check_numeric_aggregate(expected: u64, answers: Vec<u64>) {
+    let actual = answer_aggregation::aggregate(AnswerType::Unsigned, &to_numeric_answers(answers));
+    assert_eq!(
+        Result::Ok(BigUint::<DebugApi>::from(expected).to_bytes_be_buffer()),
+        actual
+    );
+}
+
+#[test]
+fn test_aggregate_unsigned() {
+    let _ = DebugApi::dummy();
+
+    check_numeric_aggregate(42, vec![42]);
+    check_numeric_aggregate(11, vec![12, 10, 11]);
+    check_numeric_aggregate(11, vec![10, 13, 11, 12]);
+    check_numeric_aggregate(0, vec![0, 0, 1000]);
+}
+
+#[test]
+fn test_aggregate_most_common() {
+    let _ = DebugApi::dummy();
+
+    check_aggregate(AnswerType::Bytes, b"42", vec![b"42"]);
+    check_aggregate(AnswerType::Bytes, b"b", vec![b"a", b"b", b"b"]);
+    check_aggregate(AnswerType::Boolean, b"\x01", vec![b"\x01", b"", b"\x01"]);
+    check_aggregate(AnswerType::Bytes, b"a", vec![b"b", b"a", b"a", b"c", b"a"]);
+}
+
+#[test]
+fn test_aggregate_requires_majority() {
+    let _ = DebugApi::dummy();
+
+    let check_no_majority = |answers: Vec<&[u8]>| {
+        assert_eq!(
+            Result::Err("no majority answer".into()),
+            answer_aggregation::aggregate(AnswerType::Bytes, &to_answers(answers))
+        );
+    };
+    // a tie is not a majority
+    check_no_majority(vec![b"a", b"b"]);
+    check_no_majority(vec![b"\x01", b"", b"", b"\x01"]);
+    // neither is the most common answer, if given by half of the nodes or less
+    check_no_majority(vec![b"b", b"a", b"c", b"a", b"b", b"d"]);
+    check_no_majority(vec![b"a", b"b", b"c"]);
+}
+
+#[test]
+fn test_aggregate_no_answers() {
+    let _ = DebugApi::dummy();
+
+    assert!(answer_aggregation::aggregate(AnswerType::Bytes, &to_answers(vec![])).is_err());
+    assert!(answer_aggregation::aggregate(AnswerType::Unsigned, &to_answers(vec![])).is_err());
+}
@@ -0,0 +1,176 @@
+use aggregator::aggregator_interface::{FieldType, Submission, SIGNED_VALUE_OFFSET_BITS};
+use aggregator::answer_bounds::AnswerDeviationLimit;
+use elrond_wasm::types::BigUint;
+use elrond_wasm_debug::DebugApi;
+
+fn to_submission(v: Vec<u32>) -> Submission<DebugApi> {
+    Submission {
+        values: v
+            .iter()
+            .map(|value| BigUint::<DebugApi>::from(*value))
+            .collect(),
+    }
+}
+
+fn to_signed_submission(v: Vec<i64>) -> Submission<DebugApi> {
+    let offset = BigUint::<DebugApi>::from(2u32).pow(SIGNED_VALUE_OFFSET_BITS as u32);
+    Submission {
+        values: v
+            .iter()
+            .map(|value| {
+                if *value < 0 {
+                    &offset - &BigUint::<DebugApi>::from(value.unsigned_abs())
+                } else {
+                    &offset + &BigUint::<DebugApi>::from(*value as u64)
+                }
+            })
+            .collect(),
+    }
+}
+
+fn check_exceeded(
+    expected: bool,
+    limit: &AnswerDeviationLimit<DebugApi>,
+    previous: Vec<u32>,
+    new: Vec<u32>,
+) {
+    let field_types = vec![FieldType::Unsigned; previous.len()];
+    check_exceeded_with_types(expected, limit, &field_types, previous, new);
+}
+
+fn check_exceeded_with_types(
+    expected: bool,
+    limit: &AnswerDeviationLimit<DebugApi>,
+    field_types: &[FieldType],
+    previous: Vec<u32>,
+    new: Vec<u32>,
+) {
+    assert_eq!(
+        expected,
+        limit.is_deviation_exceeded(field_types, &to_submission(previous), &to_submission(new))
+    );
+}
+
+fn check_signed_exceeded(
+    expected: bool,
+    limit: &AnswerDeviationLimit<DebugApi>,
+    previous: i64,
+    new: i64,
+) {
+    assert_eq!(
+        expected,
+        limit.is_deviation_exceeded(
+            &[FieldType::Signed],
+            &to_signed_submission(vec![previous]),
+            &to_signed_submission(vec![new])
+        )
+    );
+}
+
+#[test]
+fn test_unlimited_deviation() {
+    let _ = DebugApi::dummy();
+    let limit = AnswerDeviationLimit::Unlimited;
+    check_exceeded(false, &limit, vec![100], vec![1]);
+    check_exceeded(false, &limit, vec![100], vec![1_000_000]);
+}
+
+#[test]
+fn test_absolute_deviation() {
+    let _ = DebugApi::dummy();
+    let limit = AnswerDeviationLimit::Absolute(BigUint::<DebugApi>::from(10u32));
+    check_exceeded(false, &limit, vec![100], vec![100]);
+    check_exceeded(false, &limit, vec![100], vec![110]);
+    check_exceeded(false, &limit, vec![100], vec![90]);
+    check_exceeded(true, &limit, vec![100], vec![111]);
+    check_exceeded(true, &limit, vec![100], vec![89]);
+}
+
+#[test]
+fn test_basis_points_deviation() {
+    let _ = DebugApi::dummy();
+    // 5%
+    let limit = AnswerDeviationLimit::BasisPoints(500);
+    check_exceeded(false, &limit, vec![2_000], vec![2_100]);
+    check_exceeded(false, &limit, vec![2_000], vec![1_900]);
+    check_exceeded(true, &limit, vec![2_000], vec![2_101]);
+    check_exceeded(true, &limit, vec![2_000], vec![1_899]);
+    check_exceeded(true, &limit, vec![0], vec![1]);
+}
+
+#[test]
+fn test_deviation_of_any_value() {
+    let _ = DebugApi::dummy();
+    let limit = AnswerDeviationLimit::BasisPoints(1_000);
+    check_exceeded(false, &limit, vec![100, 200, 300], vec![105, 190, 320]);
+    check_exceeded(true, &limit, vec![100, 200, 300], vec![105, 190, 400]);
+}
+
+#[test]
+fn test_deviation_of_fixed_point_value() {
+    let _ = DebugApi::dummy();
+    let limit = AnswerDeviationLimit::BasisPoints(1_000);
+    let field_types = [FieldType::FixedPoint(2), FieldType::Unsigned];
+    check_exceeded_with_types(false, &limit, &field_types, vec![100, 200], vec![110, 200]);
+    check_exceeded_with_types(true, &limit, &field_types, vec![100, 200], vec![111, 200]);
+}
+
+#[test]
+fn test_deviation_ignores_booleans_and_hashes() {
+    let _ = DebugApi::dummy();
+    let limit = AnswerDeviationLimit::Absolute(BigUint::<DebugApi>::from(10u32));
+    let field_types = [
+        FieldType::Unsigned,
+        FieldType::Boolean,
+        FieldType::BytesHash,
+    ];
+    check_exceeded_with_types(
+        false,
+        &limit,
+        &field_types,
+        vec![100, 0, 1_000_000],
+        vec![105, 1, 7],
+    );
+    check_exceeded_with_types(
+        true,
+        &limit,
+        &field_types,
+        vec![100, 0, 1_000_000],
+        vec![120, 0, 1_000_000],
+    );
+}
+
+#[test]
+fn test_absolute_deviation_of_signed_value() {
+    let _ = DebugApi::dummy();
+    let limit = AnswerDeviationLimit::Absolute(BigUint::<DebugApi>::from(10u32));
+    check_signed_exceeded(false, &limit, -5, 5);
+    check_signed_exceeded(false, &limit, -100, -90);
+    check_signed_exceeded(true, &limit, -5, 6);
+    check_signed_exceeded(true, &limit, 3, -8);
+}
+
+#[test]
+fn test_basis_points_deviation_of_signed_value() {
+    let _ = DebugApi::dummy();
+    // 5%, relative to the absolute previous value
+    let limit = AnswerDeviationLimit::BasisPoints(500);
+    check_signed_exceeded(false, &limit, -2_000, -2_100);
+    check_signed_exceeded(false, &limit, -2_000, -1_900);
+    check_signed_exceeded(true, &limit, -2_000, -2_101);
+    check_signed_exceeded(true, &limit, -2_000, -1_899);
+    check_signed_exceeded(true, &limit, 100, -100);
+}
+
+#[test]
+fn test_signed_and_hash_value_bounds() {
+    let _ = DebugApi::dummy();
+    let power_of_two = |bits: usize| BigUint::<DebugApi>::from(2u32).pow(bits as u32);
+    let signed_bound = power_of_two(SIGNED_VALUE_OFFSET_BITS + 1);
+    assert!(FieldType::Signed.is_valid(&(&signed_bound - &BigUint::from(1u32))));
+    assert!(!FieldType::Signed.is_valid(&signed_bound));
+
+    let hash_bound = power_of_two(256);
+    assert!(FieldType::BytesHash.is_valid(&(&hash_bound - &BigUint::from(1u32))));
+    assert!(!FieldType::BytesHash.is_valid(&hash_bound));
+}
@@ -0,0 +1,90 @@
+use elrond_wasm::types::BigUint;
+use elrond_wasm_debug::DebugApi;
+use exchange::rate::Rate;
+
+fn big(value: u64) -> BigUint<DebugApi> {
+    BigUint::from(value)
+}
+
+fn pow10(exponent: u32) -> BigUint<DebugApi> {
+    BigUint::from(10u32).pow(exponent)
+}
+
+/// Converts `amount` units of the source token at a feed price of `price`
+/// with `price_decimals` decimals
+fn convert(
+    amount: BigUint<DebugApi>,
+    price: u64,
+    price_decimals: u8,
+    source_decimals: u8,
+    target_decimals: u8,
+) -> BigUint<DebugApi> {
+    Rate::from_price(big(price), price_decimals)
+        .between_units(source_decimals, target_decimals)
+        .convert(&amount)
+}
+
+#[test]
+fn test_convert_same_decimals() {
+    let _ = DebugApi::dummy();
+    // 2.5 tokens at a price of 1.20
+    assert_eq!(big(3_000_000), convert(big(2_500_000), 120, 2, 6, 6));
+    assert_eq!(big(0), convert(big(0), 120, 2, 6, 6));
+}
+
+#[test]
+fn test_convert_to_fewer_decimals() {
+    let _ = DebugApi::dummy();
+    // 1.5 EGLD at 30.25 USDC per EGLD is 45.375 USDC
+    let egld = big(15) * pow10(17);
+    assert_eq!(big(45_375_000), convert(egld, 3025, 2, 18, 6));
+
+    // amounts below the smallest target unit round down to zero
+    assert_eq!(big(0), convert(pow10(10), 3025, 2, 18, 6));
+}
+
+#[test]
+fn test_convert_to_more_decimals() {
+    let _ = DebugApi::dummy();
+    // 45.375 USDC at 0.033057 EGLD per USDC
+    let expected = big(1_499_961_375) * pow10(9);
+    assert_eq!(expected, convert(big(45_375_000), 33057, 6, 6, 18));
+
+    // the inverse of a 30.25 USDC/EGLD feed, from USDC to EGLD
+    let rate = Rate::<DebugApi>::from_price(big(3025), 2)
+        .inverse()
+        .between_units(6, 18);
+    assert_eq!(big(15) * pow10(17), rate.convert(&big(45_375_000)));
+}
+
+#[test]
+fn test_convert_through_route() {
+    let _ = DebugApi::dummy();
+    // MEX (18 decimals) -> USD at 0.00005, USD -> EGLD at 1/40 (EGLD/USD feed of 40.00)
+    let rate = Rate::<DebugApi>::from_price(big(5), 5)
+        .then(Rate::from_price(big(4000), 2).inverse())
+        .between_units(18, 18);
+    let mex = big(1_000_000) * pow10(18);
+    assert_eq!(big(125) * pow10(16), rate.convert(&mex));
+}
+
+#[test]
+fn test_rate_change() {
+    let _ = DebugApi::dummy();
+    let previous = Rate::<DebugApi>::from_price(big(3000), 2);
+
+    // 30.00 -> 31.50 is a change of 5%
+    let current = Rate::from_price(big(3150), 2);
+    assert!(!current.change_exceeds(&previous, 500));
+    assert!(current.change_exceeds(&previous, 499));
+
+    // 30.00 -> 28.50 is also a change of 5%, whatever the feed decimals
+    let current = Rate::from_price(big(28_500_000), 6);
+    assert!(!current.change_exceeds(&previous, 500));
+    assert!(current.change_exceeds(&previous, 499));
+
+    // the change of an inverted rate is relative to the inverted previous rate
+    let current = Rate::from_price(big(3150), 2).inverse();
+    assert!(!current.change_exceeds(&previous.clone().inverse(), 477));
+    assert!(current.change_exceeds(&previous.inverse(), 476));
+}
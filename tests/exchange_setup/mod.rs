@@ -0,0 +1,431 @@
+use elrond_wasm::{
+    elrond_codec::multi_types::OptionalValue,
+    types::{Address, EgldOrEsdtTokenIdentifier, MultiValueEncoded},
+};
+use elrond_wasm_debug::{
+    managed_address, managed_biguint, managed_buffer, managed_token_id, rust_biguint,
+    testing_framework::{BlockchainStateWrapper, ContractObjWrapper},
+    tx_mock::{TxInputESDT, TxResult},
+    DebugApi,
+};
+use elrond_wasm_modules::pause::PauseModule;
+use exchange::{
+    circuit_breaker::CircuitBreakerModule, limits::LimitsModule, liquidity::LiquidityModule,
+    EgldEsdtExchange,
+};
+use price_aggregator::{staking::StakingModule, PriceAggregator};
+use price_aggregator_proxy::PriceAggregatorModule;
+
+pub static WEGLD_TOKEN_ID: &[u8] = b"WEGLD-abcdef";
+pub static USDC_TOKEN_ID: &[u8] = b"USDC-abcdef";
+pub static MEX_TOKEN_ID: &[u8] = b"MEX-abcdef";
+pub static EGLD_TICKER: &[u8] = b"EGLD";
+pub static USD_TICKER: &[u8] = b"USD";
+pub static MEX_TICKER: &[u8] = b"MEX";
+
+/// Prices are published with 2 decimals, token amounts have none
+pub const PRICE_DECIMALS: u8 = 2;
+pub const STAKE_AMOUNT: u64 = 20;
+pub const POOL_AMOUNT: u64 = 1_000_000;
+pub const USER_AMOUNT: u64 = 10_000;
+
+pub struct ExchangeSetup<PriceAggObjBuilder, ExchangeObjBuilder>
+where
+    PriceAggObjBuilder: 'static + Copy + Fn() -> price_aggregator::ContractObj<DebugApi>,
+    ExchangeObjBuilder: 'static + Copy + Fn() -> exchange::ContractObj<DebugApi>,
+{
+    pub b_mock: BlockchainStateWrapper,
+    pub owner: Address,
+    pub oracle: Address,
+    pub user: Address,
+    pub current_timestamp: u64,
+    pub price_agg: ContractObjWrapper<price_aggregator::ContractObj<DebugApi>, PriceAggObjBuilder>,
+    pub exchange: ContractObjWrapper<exchange::ContractObj<DebugApi>, ExchangeObjBuilder>,
+}
+
+impl<PriceAggObjBuilder, ExchangeObjBuilder> ExchangeSetup<PriceAggObjBuilder, ExchangeObjBuilder>
+where
+    PriceAggObjBuilder: 'static + Copy + Fn() -> price_aggregator::ContractObj<DebugApi>,
+    ExchangeObjBuilder: 'static + Copy + Fn() -> exchange::ContractObj<DebugApi>,
+{
+    /// Deploys a price aggregator publishing the answer of a single oracle, and an exchange
+    /// between WEGLD (ticker `EGLD`) and USDC (ticker `USD`), whose pool the owner opens
+    /// with `POOL_AMOUNT` of both, for `2 * POOL_AMOUNT` shares, and a user account.
+    pub fn new(
+        price_agg_builder: PriceAggObjBuilder,
+        exchange_builder: ExchangeObjBuilder,
+    ) -> Self {
+        let rust_zero = rust_biguint!(0);
+        let mut b_mock = BlockchainStateWrapper::new();
+        let owner = b_mock.create_user_account(&rust_zero);
+        let oracle = b_mock.create_user_account(&rust_zero);
+
+        let current_timestamp = 100;
+        b_mock.set_block_timestamp(current_timestamp);
+
+        let price_agg = deploy_price_aggregator(&mut b_mock, &owner, &oracle, price_agg_builder);
+        let exchange =
+            b_mock.create_sc_account(&rust_zero, Some(&owner), exchange_builder, "exchange_path");
+
+        // init exchange
+        b_mock
+            .execute_tx(&owner, &exchange, &rust_zero, |sc| {
+                sc.init(managed_address!(price_agg.address_ref()));
+            })
+            .assert_ok();
+
+        let mut setup = Self {
+            b_mock,
+            owner,
+            oracle,
+            user: Address::zero(),
+            current_timestamp,
+            price_agg,
+            exchange,
+        };
+        for (token, ticker) in [(WEGLD_TOKEN_ID, EGLD_TICKER), (USDC_TOKEN_ID, USD_TICKER)] {
+            setup.set_token_ticker(token, ticker);
+            setup
+                .b_mock
+                .set_esdt_balance(&setup.owner, token, &rust_biguint!(POOL_AMOUNT));
+        }
+        let owner = setup.owner.clone();
+        setup
+            .add_liquidity(
+                &owner,
+                &[(WEGLD_TOKEN_ID, POOL_AMOUNT), (USDC_TOKEN_ID, POOL_AMOUNT)],
+            )
+            .assert_ok();
+        setup.user = setup.create_user();
+
+        setup
+    }
+
+    /// Creates an account holding `USER_AMOUNT` of WEGLD and USDC
+    pub fn create_user(&mut self) -> Address {
+        let user = self.b_mock.create_user_account(&rust_biguint!(0));
+        for token in [WEGLD_TOKEN_ID, USDC_TOKEN_ID] {
+            self.b_mock
+                .set_esdt_balance(&user, token, &rust_biguint!(USER_AMOUNT));
+        }
+        user
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: u64) {
+        self.current_timestamp = timestamp;
+        self.b_mock.set_block_timestamp(timestamp);
+    }
+
+    pub fn set_token_ticker(&mut self, token: &[u8], ticker: &[u8]) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.set_token_ticker(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(token)),
+                    managed_buffer!(ticker),
+                    0,
+                );
+            })
+            .assert_ok();
+    }
+
+    /// Pays the `(token, amount)` payments from `caller` into `addLiquidity`
+    pub fn add_liquidity(&mut self, caller: &Address, payments: &[(&[u8], u64)]) -> TxResult {
+        let transfers = esdt_transfers(payments);
+        self.b_mock
+            .execute_esdt_multi_transfer(caller, &self.exchange, &transfers, |sc| {
+                let _ = sc.add_liquidity();
+            })
+    }
+
+    pub fn remove_liquidity(&mut self, caller: &Address, shares: u64) -> TxResult {
+        self.b_mock
+            .execute_tx(caller, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.remove_liquidity(managed_biguint!(shares));
+            })
+    }
+
+    /// The owner, while holding all the shares, takes out the liquidity and opens
+    /// the pool again with `POOL_AMOUNT` of each of `tokens`
+    pub fn reopen_pool(&mut self, tokens: &[&[u8]]) {
+        let owner = self.owner.clone();
+        self.remove_liquidity(&owner, 2 * POOL_AMOUNT).assert_ok();
+        for token in tokens {
+            self.b_mock
+                .set_esdt_balance(&owner, token, &rust_biguint!(POOL_AMOUNT));
+        }
+        let payments: Vec<(&[u8], u64)> =
+            tokens.iter().map(|token| (*token, POOL_AMOUNT)).collect();
+        self.add_liquidity(&owner, &payments).assert_ok();
+    }
+
+    pub fn set_pair_fee(&mut self, source_token: &[u8], target_token: &[u8], fee_bps: u32) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.set_pair_fee(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(source_token)),
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(target_token)),
+                    fee_bps,
+                );
+            })
+            .assert_ok();
+    }
+
+    pub fn set_pair_spread(&mut self, source_token: &[u8], target_token: &[u8], spread_bps: u32) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.set_pair_spread(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(source_token)),
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(target_token)),
+                    spread_bps,
+                );
+            })
+            .assert_ok();
+    }
+
+    pub fn set_swap_limits(
+        &mut self,
+        token: &[u8],
+        max_per_swap: u64,
+        user_daily_cap: u64,
+        global_daily_cap: u64,
+    ) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.set_swap_limits(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(token)),
+                    managed_biguint!(max_per_swap),
+                    managed_biguint!(user_daily_cap),
+                    managed_biguint!(global_daily_cap),
+                );
+            })
+            .assert_ok();
+    }
+
+    pub fn set_reserve_floor(&mut self, token: &[u8], floor: u64) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.set_reserve_floor(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(token)),
+                    managed_biguint!(floor),
+                );
+            })
+            .assert_ok();
+    }
+
+    /// Deploys a new price aggregator, without any rounds, and points the exchange to it
+    pub fn replace_price_aggregator(&mut self, price_agg_builder: PriceAggObjBuilder) {
+        self.price_agg = deploy_price_aggregator(
+            &mut self.b_mock,
+            &self.owner,
+            &self.oracle,
+            price_agg_builder,
+        );
+        let price_agg_address = self.price_agg.address_ref().clone();
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                let _ = sc.set_price_aggregator_address(managed_address!(&price_agg_address));
+            })
+            .assert_ok();
+    }
+
+    pub fn set_max_price_age(&mut self, max_price_age: u64) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.set_max_price_age(max_price_age);
+            })
+            .assert_ok();
+    }
+
+    pub fn set_max_rate_change(&mut self, max_rate_change_bps: u32) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.set_max_rate_change(max_rate_change_bps);
+            })
+            .assert_ok();
+    }
+
+    pub fn set_max_reference_age(&mut self, max_reference_age: u64) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.set_max_reference_age(max_reference_age);
+            })
+            .assert_ok();
+    }
+
+    pub fn reset_reference_rate(&mut self, source_token: &[u8], target_token: &[u8]) {
+        self.b_mock
+            .execute_tx(&self.owner, &self.exchange, &rust_biguint!(0), |sc| {
+                sc.reset_reference_rate(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(source_token)),
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(target_token)),
+                );
+            })
+            .assert_ok();
+    }
+
+    /// Publishes a new round of the `from/to` feed, at the current timestamp
+    pub fn submit_price(&mut self, from: &[u8], to: &[u8], price: u64) {
+        let timestamp = self.current_timestamp;
+        self.b_mock
+            .execute_tx(&self.oracle, &self.price_agg, &rust_biguint!(0), |sc| {
+                sc.submit(
+                    managed_buffer!(from),
+                    managed_buffer!(to),
+                    timestamp,
+                    managed_biguint!(price),
+                );
+            })
+            .assert_ok();
+    }
+
+    /// Pays `amount` of `source_token` from `caller` into `exchange`
+    pub fn exchange(
+        &mut self,
+        caller: &Address,
+        source_token: &[u8],
+        amount: u64,
+        target_token: &[u8],
+    ) -> TxResult {
+        self.b_mock.execute_esdt_transfer(
+            caller,
+            &self.exchange,
+            source_token,
+            0,
+            &rust_biguint!(amount),
+            |sc| {
+                sc.exchange(
+                    EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(target_token)),
+                    OptionalValue::None,
+                );
+            },
+        )
+    }
+
+    /// Queries the `quote` view, which fails with the reason a swap would be refunded
+    pub fn quote(&mut self, source_token: &[u8], amount: u64, target_token: &[u8]) -> TxResult {
+        self.b_mock.execute_query(&self.exchange, |sc| {
+            let _ = sc.quote(
+                EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(source_token)),
+                managed_biguint!(amount),
+                EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(target_token)),
+            );
+        })
+    }
+
+    /// Pays the `(token, amount)` payments from `caller` into `exchangeMulti`, to be
+    /// converted into the `(token, weight_bps)` basket
+    pub fn exchange_multi(
+        &mut self,
+        caller: &Address,
+        payments: &[(&[u8], u64)],
+        basket: &[(&[u8], u32)],
+    ) -> TxResult {
+        let transfers = esdt_transfers(payments);
+        self.b_mock
+            .execute_esdt_multi_transfer(caller, &self.exchange, &transfers, |sc| {
+                let mut targets = MultiValueEncoded::new();
+                for (token, weight_bps) in basket {
+                    targets.push(
+                        (
+                            EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(*token)),
+                            *weight_bps,
+                        )
+                            .into(),
+                    );
+                }
+                sc.exchange_multi(targets);
+            })
+    }
+
+    /// Checks the WEGLD and USDC balances of `user`
+    pub fn check_user_balances(&self, user: &Address, wegld: u64, usdc: u64) {
+        self.b_mock
+            .check_esdt_balance(user, WEGLD_TOKEN_ID, &rust_biguint!(wegld));
+        self.b_mock
+            .check_esdt_balance(user, USDC_TOKEN_ID, &rust_biguint!(usdc));
+    }
+
+    /// Checks the liquidity shares of `provider`
+    pub fn check_lp_shares(&mut self, provider: &Address, expected: u64) {
+        self.b_mock
+            .execute_query(&self.exchange, |sc| {
+                let shares = sc.lp_shares(&managed_address!(provider)).get();
+                assert_eq!(shares, managed_biguint!(expected));
+            })
+            .assert_ok();
+    }
+
+    /// Checks the amount of `token` held by the exchange's pool
+    pub fn check_pool(&mut self, token: &[u8], expected: u64) {
+        self.b_mock
+            .execute_query(&self.exchange, |sc| {
+                let balance = sc
+                    .balance()
+                    .get(&EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(token)))
+                    .unwrap_or_else(|| managed_biguint!(0));
+                assert_eq!(balance, managed_biguint!(expected));
+            })
+            .assert_ok();
+        self.b_mock.check_esdt_balance(
+            self.exchange.address_ref(),
+            token,
+            &rust_biguint!(expected),
+        );
+    }
+}
+
+fn esdt_transfers(payments: &[(&[u8], u64)]) -> Vec<TxInputESDT> {
+    payments
+        .iter()
+        .map(|(token, amount)| TxInputESDT {
+            token_identifier: token.to_vec(),
+            nonce: 0,
+            value: rust_biguint!(*amount),
+        })
+        .collect()
+}
+
+/// Deploys a price aggregator publishing the answer of `oracle` alone, who stakes into it
+fn deploy_price_aggregator<PriceAggObjBuilder>(
+    b_mock: &mut BlockchainStateWrapper,
+    owner: &Address,
+    oracle: &Address,
+    price_agg_builder: PriceAggObjBuilder,
+) -> ContractObjWrapper<price_aggregator::ContractObj<DebugApi>, PriceAggObjBuilder>
+where
+    PriceAggObjBuilder: 'static + Copy + Fn() -> price_aggregator::ContractObj<DebugApi>,
+{
+    let rust_zero = rust_biguint!(0);
+    let price_agg =
+        b_mock.create_sc_account(&rust_zero, Some(owner), price_agg_builder, "price_agg_path");
+
+    b_mock
+        .execute_tx(owner, &price_agg, &rust_zero, |sc| {
+            let mut oracle_args = MultiValueEncoded::new();
+            oracle_args.push(managed_address!(oracle));
+
+            sc.init(
+                EgldOrEsdtTokenIdentifier::egld(),
+                managed_biguint!(STAKE_AMOUNT),
+                managed_biguint!(STAKE_AMOUNT),
+                1,
+                1,
+                PRICE_DECIMALS,
+                oracle_args,
+            );
+        })
+        .assert_ok();
+    b_mock.set_egld_balance(oracle, &rust_biguint!(STAKE_AMOUNT));
+    b_mock
+        .execute_tx(oracle, &price_agg, &rust_biguint!(STAKE_AMOUNT), |sc| {
+            sc.stake();
+        })
+        .assert_ok();
+    b_mock
+        .execute_tx(owner, &price_agg, &rust_zero, |sc| {
+            sc.unpause_endpoint();
+        })
+        .assert_ok();
+
+    price_agg
+}
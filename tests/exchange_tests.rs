@@ -0,0 +1,494 @@
+use elrond_wasm::types::{Address, EgldOrEsdtTokenIdentifier};
+use elrond_wasm_debug::{
+    managed_address, managed_biguint, managed_token_id, rust_biguint, DebugApi,
+};
+use exchange::limits::LimitsModule;
+
+mod exchange_setup;
+use exchange_setup::*;
+
+#[test]
+fn exchange_liquidity_providers_share_fees_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let owner = ex_setup.owner.clone();
+    let user = ex_setup.user.clone();
+    let provider = ex_setup.create_user();
+    ex_setup.check_lp_shares(&owner, 2 * POOL_AMOUNT);
+
+    // the USDC paid limits the deposit to 1/200 of the pool, the extra WEGLD is refunded
+    ex_setup
+        .add_liquidity(
+            &provider,
+            &[(WEGLD_TOKEN_ID, 10_000), (USDC_TOKEN_ID, 5_000)],
+        )
+        .assert_ok();
+    ex_setup.check_user_balances(&provider, USER_AMOUNT - 5_000, USER_AMOUNT - 5_000);
+    ex_setup.check_lp_shares(&provider, 10_000);
+    ex_setup.check_pool(WEGLD_TOKEN_ID, POOL_AMOUNT + 5_000);
+    ex_setup.check_pool(USDC_TOKEN_ID, POOL_AMOUNT + 5_000);
+
+    // 10,000 WEGLD at 30.25 USD, less a 1% spread, is 299,475 USDC, less a 1% fee of 2,994
+    ex_setup.set_pair_spread(WEGLD_TOKEN_ID, USDC_TOKEN_ID, 100);
+    ex_setup.set_pair_fee(WEGLD_TOKEN_ID, USDC_TOKEN_ID, 100);
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 10_000, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, 0, USER_AMOUNT + 296_481);
+    ex_setup.check_pool(WEGLD_TOKEN_ID, 1_015_000);
+    ex_setup.check_pool(USDC_TOKEN_ID, 708_519);
+
+    // the WEGLD paid in can not be taken out by the user who swapped it in
+    ex_setup
+        .remove_liquidity(&user, 1)
+        .assert_user_error("Not enough liquidity shares");
+
+    // the provider's slice of the pool is worth 156,256 USD at 30.25, more than the
+    // 156,250 deposited, from its part of the spread and the fee
+    ex_setup.remove_liquidity(&provider, 10_000).assert_ok();
+    ex_setup.check_user_balances(&provider, USER_AMOUNT + 49, USER_AMOUNT - 1_476);
+    ex_setup.check_lp_shares(&provider, 0);
+    ex_setup.check_pool(WEGLD_TOKEN_ID, 1_009_951);
+    ex_setup.check_pool(USDC_TOKEN_ID, 704_995);
+    ex_setup
+        .remove_liquidity(&provider, 1)
+        .assert_user_error("Not enough liquidity shares");
+}
+
+#[test]
+fn exchange_add_liquidity_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+
+    // every token of the pool has to be paid
+    ex_setup
+        .add_liquidity(&user, &[(WEGLD_TOKEN_ID, 1_000)])
+        .assert_user_error("Deposit too small");
+    ex_setup
+        .b_mock
+        .set_esdt_balance(&user, MEX_TOKEN_ID, &rust_biguint!(USER_AMOUNT));
+    ex_setup
+        .add_liquidity(
+            &user,
+            &[
+                (WEGLD_TOKEN_ID, 1_000),
+                (USDC_TOKEN_ID, 1_000),
+                (MEX_TOKEN_ID, 1_000),
+            ],
+        )
+        .assert_user_error("Token not held by the pool");
+
+    // the payments of a token are added up
+    ex_setup
+        .add_liquidity(
+            &user,
+            &[
+                (WEGLD_TOKEN_ID, 300),
+                (WEGLD_TOKEN_ID, 200),
+                (USDC_TOKEN_ID, 1_000),
+            ],
+        )
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 500, USER_AMOUNT - 500);
+    ex_setup.check_lp_shares(&user, 1_000);
+    ex_setup.check_pool(WEGLD_TOKEN_ID, POOL_AMOUNT + 500);
+    ex_setup.check_pool(USDC_TOKEN_ID, POOL_AMOUNT + 500);
+
+    ex_setup
+        .remove_liquidity(&user, 0)
+        .assert_user_error("Shares must be more than 0");
+}
+
+#[test]
+fn exchange_emptied_pool_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let owner = ex_setup.owner.clone();
+    let user = ex_setup.user.clone();
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+
+    ex_setup
+        .remove_liquidity(&owner, 2 * POOL_AMOUNT)
+        .assert_ok();
+    ex_setup.check_user_balances(&owner, POOL_AMOUNT, POOL_AMOUNT);
+    ex_setup.check_pool(WEGLD_TOKEN_ID, 0);
+    ex_setup.check_pool(USDC_TOKEN_ID, 0);
+
+    // swaps are refunded, and nobody else can reopen the pool
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT, USER_AMOUNT);
+    ex_setup
+        .add_liquidity(&user, &[(WEGLD_TOKEN_ID, 100), (USDC_TOKEN_ID, 100)])
+        .assert_user_error("Only the owner can open the pool");
+
+    ex_setup
+        .add_liquidity(&owner, &[(WEGLD_TOKEN_ID, 1_000), (USDC_TOKEN_ID, 1_000)])
+        .assert_ok();
+    ex_setup.check_lp_shares(&owner, 2_000);
+}
+
+#[test]
+fn exchange_reserve_floor_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    ex_setup.set_reserve_floor(USDC_TOKEN_ID, 994_000);
+
+    // leaves 996,975 USDC
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+
+    // would leave 993,950 USDC, so the payment is refunded
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+    ex_setup.check_pool(WEGLD_TOKEN_ID, POOL_AMOUNT + 100);
+    ex_setup.check_pool(USDC_TOKEN_ID, POOL_AMOUNT - 3025);
+    ex_setup
+        .quote(WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_user_error("Swap would take the reserve below its floor");
+
+    // exactly down to the floor
+    ex_setup.set_reserve_floor(USDC_TOKEN_ID, 993_950);
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 200, USER_AMOUNT + 6050);
+    ex_setup.check_pool(USDC_TOKEN_ID, 993_950);
+}
+
+#[test]
+fn exchange_swap_limits_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+    let other_user = ex_setup.create_user();
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 100);
+    ex_setup.set_swap_limits(WEGLD_TOKEN_ID, 200, 300, 500);
+
+    // above the limit per swap
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 201, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT, USER_AMOUNT);
+
+    // also when paid in several transfers of the token
+    ex_setup
+        .exchange_multi(
+            &user,
+            &[(WEGLD_TOKEN_ID, 101), (WEGLD_TOKEN_ID, 100)],
+            &[(USDC_TOKEN_ID, 10_000)],
+        )
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT, USER_AMOUNT);
+
+    // the daily cap of the user
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 200, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 150, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 200, USER_AMOUNT + 200);
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 300, USER_AMOUNT + 300);
+    check_remaining_capacity(&mut ex_setup, &user, Some(0), Some(200));
+
+    // the global daily cap; other tokens are not limited
+    ex_setup
+        .exchange(&other_user, WEGLD_TOKEN_ID, 200, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup
+        .exchange(&other_user, WEGLD_TOKEN_ID, 1, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&other_user, USER_AMOUNT - 200, USER_AMOUNT + 200);
+    ex_setup
+        .exchange(&other_user, USDC_TOKEN_ID, 1_000, WEGLD_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&other_user, USER_AMOUNT + 800, USER_AMOUNT - 800);
+    check_remaining_capacity(&mut ex_setup, &other_user, Some(100), Some(0));
+
+    // the caps are reset the next day
+    ex_setup.set_timestamp(86_400);
+    check_remaining_capacity(&mut ex_setup, &user, Some(300), Some(500));
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 200, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 500, USER_AMOUNT + 500);
+}
+
+fn check_remaining_capacity<PriceAggObjBuilder, ExchangeObjBuilder>(
+    ex_setup: &mut ExchangeSetup<PriceAggObjBuilder, ExchangeObjBuilder>,
+    user: &Address,
+    user_remaining: Option<u64>,
+    global_remaining: Option<u64>,
+) where
+    PriceAggObjBuilder: 'static + Copy + Fn() -> price_aggregator::ContractObj<DebugApi>,
+    ExchangeObjBuilder: 'static + Copy + Fn() -> exchange::ContractObj<DebugApi>,
+{
+    check_remaining_capacity_of(
+        ex_setup,
+        WEGLD_TOKEN_ID,
+        user,
+        user_remaining,
+        global_remaining,
+    );
+}
+
+fn check_remaining_capacity_of<PriceAggObjBuilder, ExchangeObjBuilder>(
+    ex_setup: &mut ExchangeSetup<PriceAggObjBuilder, ExchangeObjBuilder>,
+    token: &[u8],
+    user: &Address,
+    user_remaining: Option<u64>,
+    global_remaining: Option<u64>,
+) where
+    PriceAggObjBuilder: 'static + Copy + Fn() -> price_aggregator::ContractObj<DebugApi>,
+    ExchangeObjBuilder: 'static + Copy + Fn() -> exchange::ContractObj<DebugApi>,
+{
+    ex_setup
+        .b_mock
+        .execute_query(&ex_setup.exchange, |sc| {
+            let capacity = sc.get_remaining_daily_capacity(
+                EgldOrEsdtTokenIdentifier::esdt(managed_token_id!(token)),
+                managed_address!(user),
+            );
+            assert_eq!(
+                capacity.user_remaining,
+                user_remaining.map(|amount| managed_biguint!(amount))
+            );
+            assert_eq!(
+                capacity.global_remaining,
+                global_remaining.map(|amount| managed_biguint!(amount))
+            );
+        })
+        .assert_ok();
+}
+
+#[test]
+fn exchange_multi_partial_refund_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+
+    // the owner reopens the pool with MEX, which has no price feed
+    ex_setup.reopen_pool(&[WEGLD_TOKEN_ID, USDC_TOKEN_ID, MEX_TOKEN_ID]);
+    ex_setup.set_token_ticker(MEX_TOKEN_ID, MEX_TICKER);
+
+    // half of each payment is converted into USDC, the other half is refunded
+    ex_setup
+        .exchange_multi(
+            &user,
+            &[(WEGLD_TOKEN_ID, 200)],
+            &[(USDC_TOKEN_ID, 5_000), (MEX_TOKEN_ID, 5_000)],
+        )
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+    ex_setup
+        .b_mock
+        .check_esdt_balance(&user, MEX_TOKEN_ID, &rust_biguint!(0));
+    ex_setup.check_pool(WEGLD_TOKEN_ID, POOL_AMOUNT + 100);
+    ex_setup.check_pool(USDC_TOKEN_ID, POOL_AMOUNT - 3025);
+    ex_setup.check_pool(MEX_TOKEN_ID, POOL_AMOUNT);
+
+    // every leg of the USDC payment fails, so all of it is refunded
+    ex_setup
+        .exchange_multi(
+            &user,
+            &[(WEGLD_TOKEN_ID, 100), (USDC_TOKEN_ID, 3025)],
+            &[(MEX_TOKEN_ID, 10_000)],
+        )
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+    ex_setup.check_pool(MEX_TOKEN_ID, POOL_AMOUNT);
+
+    // with MEX priced like WEGLD, only the USDC payment has a feed to convert it
+    ex_setup.set_token_ticker(MEX_TOKEN_ID, EGLD_TICKER);
+    ex_setup
+        .exchange_multi(
+            &user,
+            &[(WEGLD_TOKEN_ID, 100), (USDC_TOKEN_ID, 3025)],
+            &[(MEX_TOKEN_ID, 10_000)],
+        )
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT);
+    ex_setup
+        .b_mock
+        .check_esdt_balance(&user, MEX_TOKEN_ID, &rust_biguint!(100));
+    ex_setup.check_pool(MEX_TOKEN_ID, POOL_AMOUNT - 100);
+}
+
+#[test]
+fn exchange_multi_split_payment_limits_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 100);
+    ex_setup.reopen_pool(&[WEGLD_TOKEN_ID, USDC_TOKEN_ID, MEX_TOKEN_ID]);
+    ex_setup.set_token_ticker(MEX_TOKEN_ID, EGLD_TICKER);
+    ex_setup.set_swap_limits(USDC_TOKEN_ID, 200, 250, 0);
+    let basket: &[(&[u8], u32)] = &[(WEGLD_TOKEN_ID, 5_000), (MEX_TOKEN_ID, 5_000)];
+
+    // each leg of 150 USDC is within the limit per swap, but the payment is not
+    ex_setup
+        .exchange_multi(&user, &[(USDC_TOKEN_ID, 300)], basket)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT, USER_AMOUNT);
+    ex_setup
+        .b_mock
+        .check_esdt_balance(&user, MEX_TOKEN_ID, &rust_biguint!(0));
+
+    ex_setup
+        .exchange_multi(&user, &[(USDC_TOKEN_ID, 200)], basket)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT + 100, USER_AMOUNT - 200);
+    ex_setup
+        .b_mock
+        .check_esdt_balance(&user, MEX_TOKEN_ID, &rust_biguint!(100));
+
+    // the first leg of 50 USDC would still fit in the daily cap, the payment does not
+    ex_setup
+        .exchange_multi(&user, &[(USDC_TOKEN_ID, 100)], basket)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT + 100, USER_AMOUNT - 200);
+    check_remaining_capacity_of(&mut ex_setup, USDC_TOKEN_ID, &user, Some(50), None);
+}
+
+#[test]
+fn exchange_multi_invalid_basket_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+
+    // the weights would wrap around to 10000 if they were added up unchecked
+    ex_setup
+        .exchange_multi(
+            &user,
+            &[(WEGLD_TOKEN_ID, 100)],
+            &[(USDC_TOKEN_ID, u32::MAX), (WEGLD_TOKEN_ID, 10_001)],
+        )
+        .assert_user_error("Target weights must be between 1 and 10000");
+    ex_setup
+        .exchange_multi(&user, &[(WEGLD_TOKEN_ID, 100)], &[(USDC_TOKEN_ID, 5_000)])
+        .assert_user_error("Target weights must add up to 10000");
+
+    ex_setup
+        .b_mock
+        .set_esdt_balance(&user, MEX_TOKEN_ID, &rust_biguint!(USER_AMOUNT));
+    ex_setup
+        .exchange_multi(
+            &user,
+            &[(WEGLD_TOKEN_ID, 100), (MEX_TOKEN_ID, 100)],
+            &[(USDC_TOKEN_ID, 10_000)],
+        )
+        .assert_user_error("ESDT provided as payment not supported by the exchange");
+    ex_setup.check_user_balances(&user, USER_AMOUNT, USER_AMOUNT);
+    ex_setup
+        .b_mock
+        .check_esdt_balance(&user, MEX_TOKEN_ID, &rust_biguint!(USER_AMOUNT));
+}
+
+#[test]
+fn exchange_stale_price_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    ex_setup.set_max_price_age(60);
+
+    ex_setup.set_timestamp(160);
+    ex_setup
+        .quote(WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+
+    // the feed is 61 seconds old
+    ex_setup.set_timestamp(161);
+    ex_setup
+        .quote(WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_user_error("Price too old: EGLD/USD");
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT, USER_AMOUNT);
+
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+}
+
+#[test]
+fn exchange_round_regression_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+    for _ in 0..3 {
+        ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    }
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+
+    // a new price aggregator restarts from the first round
+    ex_setup.replace_price_aggregator(price_aggregator::contract_obj);
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    ex_setup
+        .quote(WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_user_error("Price from an earlier round than already used: EGLD/USD");
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+
+    // until it reaches the round already used
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 200, USER_AMOUNT + 6050);
+}
+
+#[test]
+fn exchange_rate_change_test() {
+    let mut ex_setup = ExchangeSetup::new(price_aggregator::contract_obj, exchange::contract_obj);
+    let user = ex_setup.user.clone();
+    ex_setup.set_max_rate_change(1_000);
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3025);
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+
+    // up by more than 10% since the last swap
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 3500);
+    ex_setup
+        .quote(WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_user_error("Rate moved too much since the last swap");
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 100, USER_AMOUNT + 3025);
+
+    ex_setup.reset_reference_rate(WEGLD_TOKEN_ID, USDC_TOKEN_ID);
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 200, USER_AMOUNT + 6525);
+
+    // the rate of the last swap ages out
+    ex_setup.set_max_reference_age(3_600);
+    ex_setup.submit_price(EGLD_TICKER, USD_TICKER, 4000);
+    ex_setup.set_timestamp(3_700);
+    ex_setup
+        .quote(WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_user_error("Rate moved too much since the last swap");
+    ex_setup.set_timestamp(3_701);
+    ex_setup
+        .exchange(&user, WEGLD_TOKEN_ID, 100, USDC_TOKEN_ID)
+        .assert_ok();
+    ex_setup.check_user_balances(&user, USER_AMOUNT - 300, USER_AMOUNT + 10_525);
+}
@@ -1,48 +1,320 @@
-use elrond_wasm::types::BigUint;
+use elrond_wasm::types::{BigUint, ManagedBuffer};
 use elrond_wasm_debug::DebugApi;
-use exchange::format_biguint;
+use exchange::{append_biguint, append_fixed_precision};
+use fixed_point_format::{
+    format_signed, format_unsigned, parse_signed, parse_unsigned, to_decimal_digits, write_signed,
+    write_unsigned, DecimalDigits, Format, MagnitudeTooLarge, ParseError, MAX_MAGNITUDE_LEN,
+};
+use num_bigint::BigUint as RefBigUint;
+use num_traits::{pow, Zero};
+use proptest::prelude::*;
+
+/// Appends to a buffer which already holds a prefix
+fn appended(append: impl FnOnce(&mut ManagedBuffer<DebugApi>)) -> String {
+    let mut buffer = ManagedBuffer::new_from_bytes(b"amount: ");
+    append(&mut buffer);
+    String::from_utf8(buffer.to_boxed_bytes().into_vec()).unwrap()
+}
+
+#[test]
+fn test_append_biguint() {
+    let _ = DebugApi::dummy();
+    assert_eq!(
+        "amount: 0",
+        appended(|buffer| append_biguint(buffer, &BigUint::zero()))
+    );
+    assert_eq!(
+        "amount: 1000000000",
+        appended(|buffer| append_biguint(buffer, &BigUint::from(1000000000u64)))
+    );
+    assert_eq!(
+        "amount: 1234567890",
+        appended(|buffer| append_biguint(buffer, &BigUint::from(1234567890u64)))
+    );
+}
 
 #[test]
-fn test_format_biguint() {
+fn test_append_fixed_precision() {
     let _ = DebugApi::dummy();
     assert_eq!(
-        "0".as_bytes(),
-        format_biguint(BigUint::<DebugApi>::zero()).as_slice()
-    );
-    assert_eq!(
-        "1000000000".to_string(),
-        String::from_utf8(format_biguint(BigUint::<DebugApi>::from(1000000000u64)).to_vec())
-            .unwrap()
-    );
-    assert_eq!(
-        "1234567890".to_string(),
-        String::from_utf8(format_biguint(BigUint::<DebugApi>::from(1234567890u64)).to_vec())
-            .unwrap()
-    );
-}
-
-// TODO: fix & enable
-// #[test]
-// fn test_format_fixed_precision() {
-//     let api = DebugApi::dummy();
-//     assert_eq!(
-//         "0.00123".to_string(),
-//         String::from_utf8(format_fixed_precision(&123u64.managed_into(api.clone()), 5)).unwrap()
-//     );
-//     assert_eq!(
-//         "10000.00000".to_string(),
-//         String::from_utf8(format_fixed_precision(
-//             &1000000000u64.managed_into(api.clone()),
-//             5
-//         ))
-//         .unwrap()
-//     );
-//     assert_eq!(
-//         "123456789012345.67890".to_string(),
-//         String::from_utf8(format_fixed_precision(
-//             &12345678901234567890u64.managed_into(api.clone()),
-//             5
-//         ))
-//         .unwrap()
-//     );
-// }
+        "amount: 0.00123",
+        appended(|buffer| append_fixed_precision(buffer, &BigUint::from(123u64), 5))
+    );
+    assert_eq!(
+        "amount: 10000.00000",
+        appended(|buffer| append_fixed_precision(buffer, &BigUint::from(1000000000u64), 5))
+    );
+    assert_eq!(
+        "amount: 123456789012345.67890",
+        appended(|buffer| {
+            // above i64::MAX, which the mocked BigUint::from(u64) does not convert
+            append_fixed_precision(
+                buffer,
+                &BigUint::from_bytes_be(&12345678901234567890u64.to_be_bytes()),
+                5,
+            )
+        })
+    );
+    // beyond the previous limits of 30 digits and 18 decimals
+    assert_eq!(
+        "amount: 340282366920938463463.374607431768211455",
+        appended(|buffer| {
+            append_fixed_precision(
+                buffer,
+                &BigUint::from_bytes_be(&u128::MAX.to_be_bytes()),
+                18,
+            )
+        })
+    );
+    assert_eq!(
+        "amount: 0.0000000000000000000000000000000000000001",
+        appended(|buffer| append_fixed_precision(buffer, &BigUint::from(1u32), 40))
+    );
+}
+
+#[test]
+fn test_write_without_allocating() {
+    let mut written = Vec::new();
+    assert_eq!(
+        Ok(()),
+        write_signed(true, &[0x30, 0x39], &Format::fixed(2), &mut written)
+    );
+    assert_eq!(b"-123.45".to_vec(), written);
+
+    // the largest magnitude: 2^1024 - 1
+    let largest = [0xff; MAX_MAGNITUDE_LEN];
+    let digits = DecimalDigits::new(&largest).unwrap();
+    assert_eq!(
+        RefBigUint::from_bytes_be(&largest).to_string().into_bytes(),
+        digits.as_slice()
+    );
+    assert!(DecimalDigits::new(&[0xff; MAX_MAGNITUDE_LEN + 1]).is_none());
+    assert_eq!(
+        Err(MagnitudeTooLarge),
+        write_unsigned(
+            &[0x01; MAX_MAGNITUDE_LEN + 1],
+            &Format::integer(),
+            &mut written
+        )
+    );
+}
+
+fn format_str(magnitude_be: &[u8], format: &Format) -> String {
+    String::from_utf8(format_unsigned(magnitude_be, format)).unwrap()
+}
+
+fn normalized(magnitude_be: &[u8]) -> Vec<u8> {
+    magnitude_be
+        .iter()
+        .skip_while(|byte| **byte == 0)
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_format_options() {
+    let number = 1234567890123u64.to_be_bytes();
+    assert_eq!("1234567890123", format_str(&number, &Format::integer()));
+    assert_eq!("1234567890.123", format_str(&number, &Format::fixed(3)));
+    assert_eq!(
+        "1,234,567,890.123",
+        format_str(&number, &Format::fixed(3).with_thousands_separator(b','))
+    );
+    assert_eq!(
+        "0.0000001234567890123",
+        format_str(&number, &Format::fixed(19))
+    );
+    assert_eq!(
+        "1.234567890123e12",
+        format_str(&number, &Format::integer().with_scientific_above(12))
+    );
+    assert_eq!(
+        "1234567890123",
+        format_str(&number, &Format::integer().with_scientific_above(13))
+    );
+
+    let round = 1_500_000u64.to_be_bytes();
+    assert_eq!("1.500000", format_str(&round, &Format::fixed(6)));
+    assert_eq!(
+        "1.5",
+        format_str(&round, &Format::fixed(6).with_trimmed_zeros())
+    );
+    assert_eq!(
+        "15",
+        format_str(&round, &Format::fixed(5).with_trimmed_zeros())
+    );
+    assert_eq!(
+        "1.5e6",
+        format_str(&round, &Format::integer().with_scientific_above(3))
+    );
+    assert_eq!(
+        "1.5e5",
+        format_str(&round, &Format::fixed(1).with_scientific_above(0))
+    );
+
+    assert_eq!("0", format_str(&[], &Format::integer()));
+    assert_eq!("0.00", format_str(&[], &Format::fixed(2)));
+    assert_eq!(
+        "0",
+        format_str(&[0, 0], &Format::fixed(2).with_trimmed_zeros())
+    );
+    assert_eq!(
+        "0",
+        format_str(&[], &Format::integer().with_scientific_above(0))
+    );
+    assert_eq!(
+        "-12.5",
+        String::from_utf8(format_signed(true, &[125], &Format::fixed(1))).unwrap()
+    );
+    assert_eq!(
+        "0.0",
+        String::from_utf8(format_signed(true, &[0], &Format::fixed(1))).unwrap()
+    );
+}
+
+#[test]
+fn test_parse() {
+    let format = Format::fixed(3).with_thousands_separator(b',');
+    assert_eq!(Ok(vec![0x30, 0x39]), parse_unsigned(b"12.345", &format));
+    assert_eq!(Ok(vec![0x30, 0x39]), parse_unsigned(b"1.2345e1", &format));
+    assert_eq!(Ok(vec![0x2e, 0xe0]), parse_unsigned(b"12", &format));
+    assert_eq!(
+        Ok(1_234_567_000u64.to_be_bytes().to_vec()).map(|bytes| normalized(&bytes)),
+        parse_unsigned(b"1,234,567", &format)
+    );
+    assert_eq!(Ok(vec![]), parse_unsigned(b"0.000", &format));
+    assert_eq!(
+        Ok((true, vec![0x04, 0xd2])),
+        parse_signed(b"-1.234", &format)
+    );
+    assert_eq!(Ok(vec![]), parse_unsigned(b"-0", &format));
+
+    assert_eq!(
+        Err(ParseError::TooManyDecimals),
+        parse_unsigned(b"1.2345", &format)
+    );
+    assert_eq!(Ok(vec![0x04, 0xd2]), parse_unsigned(b"1.2340", &format));
+    assert_eq!(Err(ParseError::Negative), parse_unsigned(b"-1", &format));
+    assert_eq!(Err(ParseError::Empty), parse_unsigned(b"", &format));
+    assert_eq!(Err(ParseError::Empty), parse_unsigned(b".", &format));
+    assert_eq!(Err(ParseError::Empty), parse_unsigned(b"1e", &format));
+    assert_eq!(
+        Err(ParseError::InvalidCharacter),
+        parse_unsigned(b"1.2.3", &format)
+    );
+    assert_eq!(
+        Err(ParseError::InvalidCharacter),
+        parse_unsigned(b",1", &format)
+    );
+    assert_eq!(
+        Err(ParseError::InvalidCharacter),
+        parse_unsigned(b"1_000", &format)
+    );
+    assert_eq!(
+        Err(ParseError::ExponentTooLarge),
+        parse_unsigned(b"1e99999", &format)
+    );
+}
+
+fn arb_format() -> impl Strategy<Value = Format> {
+    (
+        0usize..40,
+        prop::option::of(prop::sample::select(vec![b',', b'_', b' ', b'\''])),
+        any::<bool>(),
+        prop::option::of(0usize..60),
+    )
+        .prop_map(
+            |(decimals, thousands_separator, trim_trailing_zeros, scientific_above_digits)| {
+                Format {
+                    decimals,
+                    thousands_separator,
+                    trim_trailing_zeros,
+                    scientific_above_digits,
+                }
+            },
+        )
+}
+
+proptest! {
+    #[test]
+    fn prop_append_biguint_matches_to_string(number in any::<u128>()) {
+        let _ = DebugApi::dummy();
+        prop_assert_eq!(
+            format!("amount: {}", number),
+            appended(|buffer| append_biguint(buffer, &BigUint::from_bytes_be(&number.to_be_bytes())))
+        );
+    }
+
+    #[test]
+    fn prop_write_matches_format(
+        magnitude in prop::collection::vec(any::<u8>(), 0..MAX_MAGNITUDE_LEN),
+        negative in any::<bool>(),
+        format in arb_format(),
+    ) {
+        let mut written = Vec::new();
+        prop_assert_eq!(Ok(()), write_signed(negative, &magnitude, &format, &mut written));
+        prop_assert_eq!(format_signed(negative, &magnitude, &format), written);
+        let digits = DecimalDigits::new(&magnitude).unwrap();
+        prop_assert_eq!(to_decimal_digits(&magnitude), digits.as_slice());
+    }
+
+    #[test]
+    fn prop_digits_match_reference(magnitude in prop::collection::vec(any::<u8>(), 0..80)) {
+        let expected = RefBigUint::from_bytes_be(&magnitude).to_string();
+        prop_assert_eq!(expected.into_bytes(), to_decimal_digits(&magnitude));
+    }
+
+    #[test]
+    fn prop_fixed_matches_reference(
+        magnitude in prop::collection::vec(any::<u8>(), 0..80),
+        decimals in 0usize..60,
+    ) {
+        let number = RefBigUint::from_bytes_be(&magnitude);
+        let scale = pow(RefBigUint::from(10u32), decimals);
+        let mut expected = (&number / &scale).to_string();
+        if decimals > 0 {
+            let fraction = (&number % &scale).to_string();
+            expected.push('.');
+            expected.push_str(&"0".repeat(decimals - fraction.len()));
+            expected.push_str(&fraction);
+        }
+        prop_assert_eq!(expected, format_str(&magnitude, &Format::fixed(decimals)));
+    }
+
+    #[test]
+    fn prop_round_trip(
+        magnitude in prop::collection::vec(any::<u8>(), 0..80),
+        negative in any::<bool>(),
+        format in arb_format(),
+    ) {
+        let formatted = format_signed(negative, &magnitude, &format);
+        let (parsed_negative, parsed) = parse_signed(&formatted, &format).unwrap();
+        prop_assert_eq!(normalized(&magnitude), parsed);
+        prop_assert_eq!(negative && !RefBigUint::from_bytes_be(&magnitude).is_zero(), parsed_negative);
+    }
+
+    #[test]
+    fn prop_separators_do_not_change_digits(
+        magnitude in prop::collection::vec(any::<u8>(), 0..40),
+        decimals in 0usize..20,
+    ) {
+        let plain = format_str(&magnitude, &Format::fixed(decimals));
+        let separated = format_str(&magnitude, &Format::fixed(decimals).with_thousands_separator(b','));
+        prop_assert_eq!(plain, separated.replace(',', ""));
+        let integer_part = separated.split('.').next().unwrap();
+        for (index, group) in integer_part.split(',').enumerate() {
+            prop_assert!(!group.is_empty() && group.len() <= 3);
+            prop_assert!(index == 0 || group.len() == 3);
+        }
+    }
+
+    #[test]
+    fn prop_scientific_is_bounded(
+        magnitude in prop::collection::vec(any::<u8>(), 0..200),
+        limit in 0usize..30,
+    ) {
+        let formatted = format_str(&magnitude, &Format::integer().with_scientific_above(limit));
+        let mantissa = formatted.split('e').next().unwrap();
+        prop_assert!(mantissa.split('.').next().unwrap().len() <= limit.max(1));
+    }
+}
@@ -29,3 +29,38 @@ fn client_request() {
 fn aggregator() {
     elrond_wasm_debug::mandos_rs("mandos/aggregator.scen.json", world());
 }
+
+#[test]
+fn aggregator_payments() {
+    elrond_wasm_debug::mandos_rs("mandos/aggregator-payments.scen.json", world());
+}
+
+#[test]
+fn aggregator_tokens() {
+    elrond_wasm_debug::mandos_rs("mandos/aggregator-tokens.scen.json", world());
+}
+
+#[test]
+fn guardian() {
+    elrond_wasm_debug::mandos_rs("mandos/guardian.scen.json", world());
+}
+
+#[test]
+fn oracle_delivery() {
+    elrond_wasm_debug::mandos_rs("mandos/oracle-delivery.scen.json", world());
+}
+
+#[test]
+fn oracle_fees() {
+    elrond_wasm_debug::mandos_rs("mandos/oracle-fees.scen.json", world());
+}
+
+#[test]
+fn oracle_cancel() {
+    elrond_wasm_debug::mandos_rs("mandos/oracle-cancel.scen.json", world());
+}
+
+#[test]
+fn oracle_job_nodes() {
+    elrond_wasm_debug::mandos_rs("mandos/oracle-job-nodes.scen.json", world());
+}
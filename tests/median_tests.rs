@@ -1,4 +1,4 @@
-use aggregator::aggregator_interface::{Submission, MAX_SUBMISSION_VALUES};
+use aggregator::aggregator_interface::{FieldType, Submission, MAX_SUBMISSION_VALUES};
 use aggregator::median;
 use elrond_wasm::arrayvec::ArrayVec;
 use elrond_wasm::types::BigUint;
@@ -11,8 +11,7 @@ fn to_vec_biguint(v: Vec<u32>) -> ArrayVec<BigUint<DebugApi>, MAX_SUBMISSION_VAL
 }
 
 fn check_median_result(expected: Option<u32>, v: Vec<u32>) {
-    let expected_biguint: Option<BigUint<DebugApi>> =
-        expected.map(|value| BigUint::<DebugApi>::from(value));
+    let expected_biguint: Option<BigUint<DebugApi>> = expected.map(BigUint::<DebugApi>::from);
     let actual_result = median::calculate::<DebugApi>(to_vec_biguint(v));
     assert_eq!(Result::Ok(expected_biguint), actual_result);
 }
@@ -50,7 +49,8 @@ fn test_median_equal() {
 
 #[test]
 fn test_median_submission_empty() {
-    let actual_result = median::calculate_submission_median::<DebugApi>(ArrayVec::new()).unwrap();
+    let actual_result =
+        median::calculate_submission_median::<DebugApi>(ArrayVec::new(), &[]).unwrap();
     assert!(actual_result.is_none());
 }
 
@@ -71,8 +71,56 @@ fn test_median_submission() {
     a_b_as_vec.push(submission_a);
     a_b_as_vec.push(submission_b);
 
-    let actual_result = median::calculate_submission_median(a_b_as_vec)
+    let field_types = [FieldType::Unsigned; 7];
+    let actual_result = median::calculate_submission_median(a_b_as_vec, &field_types)
         .unwrap()
         .unwrap();
     assert_eq!(actual_result.values, expected_submission_result.values);
 }
+
+#[test]
+fn test_lower_middle() {
+    let _ = DebugApi::dummy();
+    let check = |expected: Option<u32>, v: Vec<u32>| {
+        let expected = expected.map(BigUint::<DebugApi>::from);
+        assert_eq!(
+            Result::Ok(expected),
+            median::calculate_lower_middle::<DebugApi>(to_vec_biguint(v))
+        );
+    };
+    check(None, vec![]);
+    check(Some(11), vec![12, 10, 11]);
+    check(Some(10), vec![10, 11]);
+    check(Some(0), vec![1, 0, 1, 0]);
+    check(Some(1), vec![1, 0, 1, 1]);
+    check(Some(7), vec![3, 7, 7, 9]);
+}
+
+#[test]
+fn test_median_submission_by_field_type() {
+    let _ = DebugApi::dummy();
+    let submissions = [
+        vec![100, 0, 5, 1],
+        vec![110, 1, 9, 1],
+        vec![120, 1, 9, 0],
+        vec![130, 0, 5, 0],
+    ];
+    let mut as_vec = ArrayVec::new();
+    for values in submissions {
+        as_vec.push(Submission {
+            values: to_vec_biguint(values),
+        });
+    }
+    let field_types = [
+        FieldType::FixedPoint(2),
+        FieldType::Boolean,
+        FieldType::BytesHash,
+        FieldType::Boolean,
+    ];
+
+    let actual_result = median::calculate_submission_median(as_vec, &field_types)
+        .unwrap()
+        .unwrap();
+    // numbers are averaged, while booleans and hashes keep one of the submitted values
+    assert_eq!(actual_result.values, to_vec_biguint(vec![115, 0, 5, 0]));
+}
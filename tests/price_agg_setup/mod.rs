@@ -1,4 +1,4 @@
-use elrond_wasm::types::{Address, MultiValueEncoded, EgldOrEsdtTokenIdentifier};
+use elrond_wasm::types::{Address, EgldOrEsdtTokenIdentifier, MultiValueEncoded};
 use elrond_wasm_debug::{
     managed_address, managed_biguint, managed_buffer, rust_biguint,
     testing_framework::{BlockchainStateWrapper, ContractObjWrapper},
@@ -69,10 +69,10 @@ where
             })
             .assert_ok();
 
-        for i in 0..4 {
+        for oracle in oracles.iter().take(4) {
             b_mock
                 .execute_tx(
-                    &oracles[i],
+                    oracle,
                     &price_agg,
                     &rust_biguint!(STAKE_AMOUNT),
                     |sc| {
@@ -0,0 +1,353 @@
+use request_cbor::{
+    validate_parameters, DecodeError, Decoder, Encoder, Sink, Token, ValidationError,
+    MAX_NESTING_DEPTH,
+};
+
+struct VecSink(Vec<u8>);
+
+impl Sink for VecSink {
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+}
+
+fn encode(write: impl FnOnce(&mut Encoder<VecSink>)) -> Vec<u8> {
+    let mut encoder = Encoder::new(VecSink(Vec::new()));
+    write(&mut encoder);
+    encoder.into_sink().0
+}
+
+fn decode(input: &[u8]) -> Result<Vec<Token<'_>>, DecodeError> {
+    let mut decoder = Decoder::new(input);
+    let mut tokens = Vec::new();
+    while let Some(token) = decoder.next_token()? {
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn check_encoding(expected: &[u8], write: impl FnOnce(&mut Encoder<VecSink>)) {
+    assert_eq!(expected, encode(write).as_slice());
+}
+
+#[test]
+fn test_encode_integers() {
+    // examples from RFC 8949, appendix A
+    check_encoding(&[0x00], |e| e.write_uint(0));
+    check_encoding(&[0x17], |e| e.write_uint(23));
+    check_encoding(&[0x18, 0x18], |e| e.write_uint(24));
+    check_encoding(&[0x18, 0x64], |e| e.write_uint(100));
+    check_encoding(&[0x19, 0x03, 0xe8], |e| e.write_uint(1000));
+    check_encoding(&[0x1a, 0x00, 0x0f, 0x42, 0x40], |e| e.write_uint(1_000_000));
+    check_encoding(
+        &[0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00],
+        |e| e.write_uint(1_000_000_000_000),
+    );
+    check_encoding(
+        &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        |e| e.write_uint(u64::MAX),
+    );
+    check_encoding(&[0x20], |e| e.write_int(-1));
+    check_encoding(&[0x29], |e| e.write_int(-10));
+    check_encoding(&[0x38, 0x63], |e| e.write_int(-100));
+    check_encoding(&[0x39, 0x03, 0xe7], |e| e.write_int(-1000));
+    check_encoding(
+        &[0x3b, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+        |e| e.write_int(i64::MIN),
+    );
+}
+
+#[test]
+fn test_encode_big_integers() {
+    // 18446744073709551616 = 2^64
+    let two_pow_64 = [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    check_encoding(
+        &[
+            0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+        |e| e.write_biguint(&two_pow_64),
+    );
+    // -18446744073709551617 = -1 - 2^64
+    check_encoding(
+        &[
+            0xc3, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+        |e| e.write_negative_biguint(&two_pow_64),
+    );
+
+    // values which fit in 64 bits are written as plain integers
+    check_encoding(&[0x18, 0x64], |e| e.write_biguint(&[0x00, 0x00, 0x64]));
+    check_encoding(&[0x00], |e| e.write_biguint(&[]));
+    check_encoding(&[0x38, 0x63], |e| e.write_negative_biguint(&[0x63]));
+}
+
+#[test]
+fn test_encode_strings_and_simple_values() {
+    check_encoding(&[0x40], |e| e.write_bytes(&[]));
+    check_encoding(&[0x44, 0x01, 0x02, 0x03, 0x04], |e| {
+        e.write_bytes(&[1, 2, 3, 4])
+    });
+    check_encoding(&[0x60], |e| e.write_str(""));
+    check_encoding(&[0x64, 0x49, 0x45, 0x54, 0x46], |e| e.write_str("IETF"));
+    check_encoding(&[0x62, 0xc3, 0xbc], |e| e.write_str("\u{00fc}"));
+    check_encoding(&[0xf4], |e| e.write_bool(false));
+    check_encoding(&[0xf5], |e| e.write_bool(true));
+    check_encoding(&[0xf6], |e| e.write_null());
+}
+
+#[test]
+fn test_encode_collections() {
+    // [1, [2, 3], [4, 5]]
+    check_encoding(&[0x83, 0x01, 0x82, 0x02, 0x03, 0x82, 0x04, 0x05], |e| {
+        e.begin_array(3);
+        e.write_uint(1);
+        e.begin_array(2);
+        e.write_uint(2);
+        e.write_uint(3);
+        e.begin_array(2);
+        e.write_uint(4);
+        e.write_uint(5);
+    });
+    // {"a": 1, "b": [2, 3]}
+    check_encoding(
+        &[0xa2, 0x61, 0x61, 0x01, 0x61, 0x62, 0x82, 0x02, 0x03],
+        |e| {
+            e.begin_map(2);
+            e.write_str("a");
+            e.write_uint(1);
+            e.write_str("b");
+            e.begin_array(2);
+            e.write_uint(2);
+            e.write_uint(3);
+        },
+    );
+    // {_ "a": 1, "b": [_ 2, 3]}
+    check_encoding(
+        &[
+            0xbf, 0x61, 0x61, 0x01, 0x61, 0x62, 0x9f, 0x02, 0x03, 0xff, 0xff,
+        ],
+        |e| {
+            e.begin_indefinite_map();
+            e.write_str("a");
+            e.write_uint(1);
+            e.write_str("b");
+            e.begin_indefinite_array();
+            e.write_uint(2);
+            e.write_uint(3);
+            e.end_indefinite();
+            e.end_indefinite();
+        },
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let encoded = encode(|e| {
+        e.write_str("get");
+        e.write_str("https://example.com/price?pair=EGLD-USD");
+        e.write_str("path");
+        e.begin_indefinite_array();
+        e.write_str("data");
+        e.write_uint(0);
+        e.write_str("price");
+        e.end_indefinite();
+        e.write_str("times");
+        e.write_uint(1_000_000_000_000_000_000);
+        e.write_str("offset");
+        e.write_int(-300);
+        e.write_str("big");
+        e.write_biguint(&[0xff; 12]);
+        e.write_str("options");
+        e.begin_map(3);
+        e.write_str("strict");
+        e.write_bool(true);
+        e.write_str("fallback");
+        e.write_null();
+        e.write_str("salt");
+        e.write_bytes(&[0xde, 0xad, 0xbe, 0xef]);
+    });
+
+    let expected = vec![
+        Token::Text("get"),
+        Token::Text("https://example.com/price?pair=EGLD-USD"),
+        Token::Text("path"),
+        Token::Array(None),
+        Token::Text("data"),
+        Token::Unsigned(0),
+        Token::Text("price"),
+        Token::Break,
+        Token::Text("times"),
+        Token::Unsigned(1_000_000_000_000_000_000),
+        Token::Text("offset"),
+        Token::Negative(299),
+        Token::Text("big"),
+        Token::Tag(2),
+        Token::Bytes(&[0xff; 12]),
+        Token::Text("options"),
+        Token::Map(Some(3)),
+        Token::Text("strict"),
+        Token::Bool(true),
+        Token::Text("fallback"),
+        Token::Null,
+        Token::Text("salt"),
+        Token::Bytes(&[0xde, 0xad, 0xbe, 0xef]),
+    ];
+    assert_eq!(Ok(expected), decode(&encoded));
+}
+
+#[test]
+fn test_round_trip_integer_boundaries() {
+    let values = [
+        0,
+        23,
+        24,
+        255,
+        256,
+        65_535,
+        65_536,
+        4_294_967_295,
+        4_294_967_296,
+        u64::MAX,
+    ];
+    for value in values.iter() {
+        let encoded = encode(|e| e.write_uint(*value));
+        assert_eq!(Ok(vec![Token::Unsigned(*value)]), decode(&encoded));
+
+        let encoded = encode(|e| e.write_negative_biguint(&value.to_be_bytes()));
+        assert_eq!(Ok(vec![Token::Negative(*value)]), decode(&encoded));
+    }
+}
+
+#[test]
+fn test_decode_errors() {
+    // truncated argument and string
+    assert_eq!(Err(DecodeError::UnexpectedEnd), decode(&[0x19, 0x03]));
+    assert_eq!(Err(DecodeError::UnexpectedEnd), decode(&[0x64, 0x49, 0x45]));
+    // a length which does not fit in the input
+    assert_eq!(
+        Err(DecodeError::UnexpectedEnd),
+        decode(&[0x5b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+    );
+    // invalid UTF-8 text
+    assert_eq!(Err(DecodeError::InvalidUtf8), decode(&[0x61, 0xff]));
+    // floats, reserved additional information, indefinite length strings
+    assert_eq!(
+        Err(DecodeError::UnsupportedItem(0xf9)),
+        decode(&[0xf9, 0x3c, 0x00])
+    );
+    assert_eq!(Err(DecodeError::UnsupportedItem(0x1c)), decode(&[0x1c]));
+    assert_eq!(
+        Err(DecodeError::UnsupportedItem(0x7f)),
+        decode(&[0x7f, 0xff])
+    );
+}
+
+#[test]
+fn test_validate_parameters() {
+    assert_eq!(Ok(()), validate_parameters(&[]));
+    let parameters = encode(|e| {
+        e.write_str("pair");
+        e.write_str("EGLD-USD");
+        e.write_str("amount");
+        e.write_biguint(&[0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        e.write_str("path");
+        e.begin_indefinite_array();
+        e.write_str("data");
+        e.begin_map(1);
+        e.write_uint(1);
+        e.write_null();
+        e.end_indefinite();
+        e.write_str("done");
+        e.write_bool(true);
+    });
+    assert_eq!(Ok(()), validate_parameters(&parameters));
+}
+
+#[test]
+fn test_validate_parameters_errors() {
+    // keys must be text strings
+    let parameters = encode(|e| {
+        e.write_uint(1);
+        e.write_str("value");
+    });
+    assert_eq!(
+        Err(ValidationError::InvalidKey),
+        validate_parameters(&parameters)
+    );
+    // a key without a value, and an array cut short
+    let parameters = encode(|e| e.write_str("pair"));
+    assert_eq!(
+        Err(ValidationError::MissingValue),
+        validate_parameters(&parameters)
+    );
+    let parameters = encode(|e| {
+        e.write_str("path");
+        e.begin_array(2);
+        e.write_str("data");
+    });
+    assert_eq!(
+        Err(ValidationError::MissingValue),
+        validate_parameters(&parameters)
+    );
+    // an indefinite length map without its break, and a break outside of one
+    let parameters = encode(|e| {
+        e.write_str("map");
+        e.begin_indefinite_map();
+        e.write_str("key");
+        e.write_uint(1);
+    });
+    assert_eq!(
+        Err(ValidationError::MissingValue),
+        validate_parameters(&parameters)
+    );
+    let parameters = encode(|e| {
+        e.write_str("pair");
+        e.end_indefinite();
+    });
+    assert_eq!(
+        Err(ValidationError::UnexpectedBreak),
+        validate_parameters(&parameters)
+    );
+    // only bignum tags, around byte strings
+    let parameters = encode(|e| {
+        e.write_str("time");
+        e.write_tag(1);
+        e.write_uint(0);
+    });
+    assert_eq!(
+        Err(ValidationError::InvalidTag),
+        validate_parameters(&parameters)
+    );
+    let parameters = encode(|e| {
+        e.write_str("amount");
+        e.write_tag(2);
+        e.write_str("1");
+    });
+    assert_eq!(
+        Err(ValidationError::InvalidTag),
+        validate_parameters(&parameters)
+    );
+    // decoding errors
+    assert_eq!(
+        Err(ValidationError::Decode(DecodeError::UnexpectedEnd)),
+        validate_parameters(&[0x64, 0x70, 0x61])
+    );
+}
+
+#[test]
+fn test_validate_parameters_nesting() {
+    let nested = |depth: usize| {
+        encode(|e| {
+            e.write_str("path");
+            for _ in 0..depth {
+                e.begin_array(1);
+            }
+            e.write_null();
+        })
+    };
+    assert_eq!(Ok(()), validate_parameters(&nested(MAX_NESTING_DEPTH)));
+    assert_eq!(
+        Err(ValidationError::TooDeep),
+        validate_parameters(&nested(MAX_NESTING_DEPTH + 1))
+    );
+}